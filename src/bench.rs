@@ -0,0 +1,59 @@
+use crate::finite_ring::FiniteRing;
+use crate::identities::{Identity, Zero};
+use crate::modint::ModInt;
+use crate::montgomery::MontgomeryModInt;
+use crate::polynomial::Polynomial;
+use crate::solver::solutions;
+use std::ops::{AddAssign, Mul, MulAssign};
+use std::time::{Duration, Instant};
+
+/// ベンチマークに使う法
+const BENCH_P: u64 = 1_000_003;
+
+/// f と g の次数。剰余演算の速さの差が出るよう、ある程度大きくしている。
+const DEGREE: u64 = 32;
+
+/// f の i 次の係数
+fn f_coefficient(i: u64) -> u64 {
+    i * i + 3 * i + 1
+}
+
+/// g の i 次の係数
+fn g_coefficient(i: u64) -> u64 {
+    2 * i * i * i + 5
+}
+
+/// 係数型```T```で方程式を解き、解の個数と掛かった時間を返す
+fn measure<T>(new: fn(u64) -> T) -> (usize, Duration)
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+{
+    let f: Vec<T> = (0..=DEGREE).map(|i| new(f_coefficient(i))).collect();
+    let g: Vec<T> = (0..=DEGREE).map(|i| new(g_coefficient(i))).collect();
+    let f = Polynomial::new(&f);
+    let g = Polynomial::new(&g);
+    let start = Instant::now();
+    let n = solutions(&f, &g).count();
+    (n, start.elapsed())
+}
+
+/// ```ModInt```と```MontgomeryModInt```で同じ方程式を解き、掛かった時間を比べる。
+///
+/// 最適化したビルド（```cargo run --release -- --bench```）で実行すること。
+pub fn run() {
+    println!(
+        "mod {} で両辺が{}次の方程式 f(x) = g(y) を解く時間を比べます。",
+        BENCH_P, DEGREE
+    );
+
+    let (n, plain) = measure(ModInt::<BENCH_P>::new);
+    println!("ModInt:           {:>8.3} 秒（解の個数 {}）", plain.as_secs_f64(), n);
+    let (m, montgomery) = measure(MontgomeryModInt::<BENCH_P>::new);
+    println!("MontgomeryModInt: {:>8.3} 秒（解の個数 {}）", montgomery.as_secs_f64(), m);
+
+    assert_eq!(n, m, "ModInt と MontgomeryModInt で解の個数が異なります");
+    println!(
+        "MontgomeryModInt は ModInt の {:.2} 倍の速さでした。",
+        plain.as_secs_f64() / montgomery.as_secs_f64()
+    );
+}
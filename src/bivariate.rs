@@ -0,0 +1,366 @@
+use crate::identities::{Identity, Zero};
+use crate::polynomial::Polynomial;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub};
+
+/// 2変数多項式 F(x, y) = Σ a_{ij} x^i y^j。
+///
+/// 係数が零でない項だけを、指数の組 (i, j) をキーとして持つ（疎な表現）。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BivariatePolynomial<T> {
+    terms: BTreeMap<(usize, usize), T>,
+}
+
+impl<T: Zero + Copy + Eq> BivariatePolynomial<T> {
+    /// 指数の組と係数の組の列から作る。同じ指数の組は足し合わせる。
+    pub fn new(terms: &[((usize, usize), T)]) -> Self
+    where
+        T: Add<Output = T>,
+    {
+        let mut f = BivariatePolynomial {
+            terms: BTreeMap::new(),
+        };
+        for &(e, c) in terms {
+            f.add_term(e, c);
+        }
+        f
+    }
+
+    /// x の多項式 f(x) を2変数多項式とみなす
+    pub fn from_x(f: &Polynomial<T>) -> Self
+    where
+        T: Add<Output = T>,
+    {
+        let terms: Vec<((usize, usize), T)> = f
+            .coefficients()
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| ((i, 0), c))
+            .collect();
+        Self::new(&terms)
+    }
+
+    /// y の多項式 g(y) を2変数多項式とみなす
+    pub fn from_y(g: &Polynomial<T>) -> Self
+    where
+        T: Add<Output = T>,
+    {
+        let terms: Vec<((usize, usize), T)> = g
+            .coefficients()
+            .iter()
+            .enumerate()
+            .map(|(j, &c)| ((0, j), c))
+            .collect();
+        Self::new(&terms)
+    }
+
+    /// x^i y^j の項に```c```を足す
+    pub fn add_term(&mut self, e: (usize, usize), c: T)
+    where
+        T: Add<Output = T>,
+    {
+        let sum = match self.terms.get(&e) {
+            Some(&a) => a + c,
+            None => c,
+        };
+        if sum == T::zero() {
+            self.terms.remove(&e);
+        } else {
+            self.terms.insert(e, sum);
+        }
+    }
+
+    /// x^i y^j の係数
+    pub fn coefficient(&self, i: usize, j: usize) -> T {
+        self.terms.get(&(i, j)).copied().unwrap_or_else(T::zero)
+    }
+
+    /// 零でない項を、指数の組 (i, j) の辞書式順に返すイテレータ
+    pub fn terms(&self) -> impl Iterator<Item = ((usize, usize), T)> + '_ {
+        self.terms.iter().map(|(&e, &c)| (e, c))
+    }
+
+    /// 零多項式かどうか
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// 全次数。零多項式は```0```とする。
+    pub fn deg(&self) -> usize {
+        self.terms.keys().map(|&(i, j)| i + j).max().unwrap_or(0)
+    }
+
+    /// x についての次数
+    pub fn deg_x(&self) -> usize {
+        self.terms.keys().map(|&(i, _)| i).max().unwrap_or(0)
+    }
+
+    /// y についての次数
+    pub fn deg_y(&self) -> usize {
+        self.terms.keys().map(|&(_, j)| j).max().unwrap_or(0)
+    }
+
+    /// xy のような x と y の積の項を含まず、a(x) + b(y) の形に書けるかどうか
+    pub fn is_separated(&self) -> bool {
+        self.terms.keys().all(|&(i, j)| i == 0 || j == 0)
+    }
+}
+
+impl<T> BivariatePolynomial<T>
+where
+    T: Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+{
+    /// 多項式に代入する。
+    pub fn evaluate(&self, x: T, y: T) -> T {
+        let mut ans = T::zero();
+        for (&(i, j), &c) in &self.terms {
+            ans += c * power(x, i) * power(y, j);
+        }
+        ans
+    }
+
+    /// x に```x```を代入した y の多項式 F(x, y)
+    pub fn substitute_x(&self, x: T) -> Polynomial<T> {
+        let mut v = vec![T::zero(); self.deg_y() + 1];
+        for (&(i, j), &c) in &self.terms {
+            v[j] += c * power(x, i);
+        }
+        Polynomial::new(&v)
+    }
+
+    /// y に```y```を代入した x の多項式 F(x, y)
+    pub fn substitute_y(&self, y: T) -> Polynomial<T> {
+        let mut v = vec![T::zero(); self.deg_x() + 1];
+        for (&(i, j), &c) in &self.terms {
+            v[i] += c * power(y, j);
+        }
+        Polynomial::new(&v)
+    }
+}
+
+/// ```t^e```
+fn power<T: Identity + MulAssign + Copy>(t: T, e: usize) -> T {
+    let mut ans = T::identity();
+    for _ in 0..e {
+        ans *= t;
+    }
+    ans
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+///
+/// 全次数の低い順に、同じ次数の中では x の次数の高い順に並べる（例: 1 + 2x + y^2 + x^2y）。
+impl<T: fmt::Display + Zero + Identity + Eq> fmt::Display for BivariatePolynomial<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut keys: Vec<&(usize, usize)> = self.terms.keys().collect();
+        keys.sort_by_key(|&&(i, j)| (i + j, std::cmp::Reverse(i)));
+        let mut terms: Vec<String> = Vec::new();
+        for &(i, j) in keys {
+            let c = &self.terms[&(i, j)];
+            let mut s = String::new();
+            if *c != T::identity() || (i == 0 && j == 0) {
+                let c = c.to_string();
+                // 係数自体が和で表示される場合（拡大体の元など）は括弧でくくる
+                if c.contains(' ') && (i, j) != (0, 0) {
+                    s.push_str(&format!("({})", c));
+                } else {
+                    s.push_str(&c);
+                }
+            }
+            for (name, e) in [("x", i), ("y", j)] {
+                if e >= 1 {
+                    s.push_str(name);
+                }
+                if e > 1 {
+                    s.push('^');
+                    s.push_str(&e.to_string());
+                }
+            }
+            terms.push(s);
+        }
+        if terms.is_empty() {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", terms.join(" + "))
+        }
+    }
+}
+
+/// 足し算の実装
+impl<T: Zero + Add<Output = T> + Copy + Eq> Add for BivariatePolynomial<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let mut f = self;
+        for (e, c) in rhs.terms {
+            f.add_term(e, c);
+        }
+        f
+    }
+}
+
+/// unary negation の実装
+impl<T: Zero + Neg<Output = T> + Copy + Eq> Neg for BivariatePolynomial<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        BivariatePolynomial {
+            terms: self.terms.into_iter().map(|(e, c)| (e, -c)).collect(),
+        }
+    }
+}
+
+/// 引き算の実装
+impl<T> Sub for BivariatePolynomial<T>
+where
+    T: Zero + Add<Output = T> + Neg<Output = T> + Copy + Eq,
+{
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+/// 掛け算の実装。項の個数を s, t として O(st log(st))。
+impl<T> Mul for BivariatePolynomial<T>
+where
+    T: Zero + Add<Output = T> + Mul<Output = T> + Copy + Eq,
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let mut f = BivariatePolynomial {
+            terms: BTreeMap::new(),
+        };
+        for (&(i, j), &a) in &self.terms {
+            for (&(k, l), &b) in &rhs.terms {
+                f.add_term((i + k, j + l), a * b);
+            }
+        }
+        f
+    }
+}
+
+impl<T: Zero + Add<Output = T> + Copy + Eq> Zero for BivariatePolynomial<T> {
+    fn zero() -> Self {
+        BivariatePolynomial {
+            terms: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Zero + Identity + Add<Output = T> + Copy + Eq> Identity for BivariatePolynomial<T> {
+    fn identity() -> Self {
+        BivariatePolynomial::new(&[((0, 0), T::identity())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::galois_field::{GaloisField, GfElem};
+    use crate::parser::parse_bivariate;
+    use crate::random::Random;
+    use crate::solver::{count_bivariate_solutions, solve_bivariate, solve_bivariate_brute_force};
+    use crate::testing::random_bivariate;
+
+    #[test]
+    fn solver_matches_brute_force() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7, 11] {
+            let _context = DynModContext::new(p);
+            for _ in 0..30 {
+                let f = random_bivariate::<DynModInt>(3, &mut random);
+                let expected = solve_bivariate_brute_force(&f);
+                assert_eq!(solve_bivariate(&f), expected, "{} mod {}", f, p);
+                assert_eq!(
+                    count_bivariate_solutions(&f),
+                    expected.size() as u64,
+                    "{} mod {}",
+                    f,
+                    p
+                );
+            }
+        }
+        let _field = GaloisField::with_degree(2, 2).unwrap();
+        for _ in 0..30 {
+            let f = random_bivariate::<GfElem>(3, &mut random);
+            assert_eq!(
+                solve_bivariate(&f),
+                solve_bivariate_brute_force(&f),
+                "{}",
+                f
+            );
+        }
+    }
+
+    #[test]
+    fn vanishing_fiber() {
+        // x(y^2 - 1) = 0 は x = 0 のすべての y と、y = ±1 のすべての x を解にもつ
+        let _context = DynModContext::new(5);
+        let one = DynModInt::identity();
+        let f = BivariatePolynomial::new(&[((1, 2), one), ((1, 0), DynModInt::new(4))]);
+        assert_eq!(count_bivariate_solutions(&f), 5 + 2 * 4);
+        assert_eq!(solve_bivariate(&f), solve_bivariate_brute_force(&f));
+        assert!(BivariatePolynomial::<DynModInt>::zero().is_zero());
+        assert_eq!(
+            count_bivariate_solutions(&BivariatePolynomial::<DynModInt>::zero()),
+            25
+        );
+    }
+
+    #[test]
+    fn substitution_and_arithmetic_agree_with_evaluation() {
+        let mut random = Random::default();
+        let _context = DynModContext::new(7);
+        for _ in 0..20 {
+            let f = random_bivariate::<DynModInt>(3, &mut random);
+            let g = random_bivariate::<DynModInt>(3, &mut random);
+            for i in 0..7 {
+                for j in 0..7 {
+                    let (x, y) = (DynModInt::new(i), DynModInt::new(j));
+                    let (a, b) = (f.evaluate(x, y), g.evaluate(x, y));
+                    assert_eq!(Polynomial::evaluate(&f.substitute_x(x), y), a);
+                    assert_eq!(Polynomial::evaluate(&f.substitute_y(y), x), a);
+                    assert_eq!((f.clone() + g.clone()).evaluate(x, y), a + b);
+                    assert_eq!((f.clone() - g.clone()).evaluate(x, y), a - b);
+                    assert_eq!((f.clone() * g.clone()).evaluate(x, y), a * b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn degrees_and_separation() {
+        let _context = DynModContext::new(7);
+        let f = Polynomial::new(&[DynModInt::new(1), DynModInt::new(2)]);
+        let g = Polynomial::new(&[DynModInt::new(0), DynModInt::new(0), DynModInt::new(3)]);
+        let h = BivariatePolynomial::from_x(&f) - BivariatePolynomial::from_y(&g);
+        assert!(h.is_separated());
+        assert_eq!((h.deg(), h.deg_x(), h.deg_y()), (2, 1, 2));
+        assert_eq!(h.coefficient(0, 2), DynModInt::new(4));
+        let xy = BivariatePolynomial::new(&[((1, 1), DynModInt::identity())]);
+        assert!(!(h.clone() + xy.clone()).is_separated());
+        assert_eq!((h * xy).deg(), 4);
+    }
+
+    #[test]
+    fn display_parses_back() {
+        let mut random = Random::default();
+        for &p in &[2, 5, 101] {
+            let _context = DynModContext::new(p);
+            for _ in 0..30 {
+                let f = random_bivariate::<DynModInt>(3, &mut random);
+                let input = format!("{} = 0", f);
+                assert_eq!(parse_bivariate(&input), Ok(f), "{}", input);
+            }
+        }
+        let _context = DynModContext::new(7);
+        let f = BivariatePolynomial::new(&[
+            ((0, 0), DynModInt::new(1)),
+            ((1, 0), DynModInt::new(2)),
+            ((0, 2), DynModInt::new(1)),
+            ((2, 1), DynModInt::new(1)),
+        ]);
+        assert_eq!(f.to_string(), "1 + 2x + y^2 + x^2y");
+    }
+}
@@ -0,0 +1,577 @@
+use crate::galois_field::GaloisFieldError;
+use crate::parser::ParseError;
+use std::fmt;
+use std::io::BufRead;
+
+/// コマンドラインの使い方
+pub const USAGE: &str = "\
+使い方: algebraic-equation-solver-in-fp-2var [オプション]
+
+オプション:
+  -p, --prime <p>      標数 p（2以上の整数）
+      --f <a0,a1,...>  f の係数を0次から順にカンマ区切りで与える
+      --g <b0,b1,...>  g の係数を0次から順にカンマ区切りで与える
+      --sum <a0,a1,...>
+                       n 変数の方程式 f_1(x_1) + ... + f_n(x_n) = c の f_i の係数を与える
+                       f_1 から順に1つずつ、変数の個数だけ繰り返して与える
+      --value <c>      --sum の方程式の右辺 c（省略時は 0）
+      --extension <k>  F_p の k 次拡大体 F_{p^k} の上で解く（p は素数）
+      --modulus <m0,m1,...>
+                       F_{p^k} = F_p[t]/(m(t)) を定める k 次の既約多項式 m の係数を0次から順に与える
+  -e, --equation <式>  方程式を式で与える（例: \"x^3 + 2x + 1 = y^2 + 4\"）
+                       \"x^2 + xy = y^3 + 1\" のように x と y の積の項を含んでもよい
+  -c, --count          解を列挙せず、解の個数だけを表示する
+                       roots ではすべての x を調べるので、p が 2^26 以下の場合だけ使える
+      --limit <n>      (x, y) の辞書式順で最初の n 個の解だけを求める
+      --projective     射影閉包の無限遠の点も含めて求める（無限遠の点は (x : y : 0) と表示する）
+      --singular       アフィン特異点を重複度と接錐とともに表示する（p は素数）
+                       直線全体が特異点になる場合は p が 2^26 以下の場合だけ使える
+      --stream         解を集めずに1行に1つずつ順に出力する
+      --factor <c>     f(x) - c と g(y) - c を既約因子に分解して表示する（p は素数）
+      --discriminant   f, g の判別式と、f(x) = c, g(y) = c が重根を持つ c を表示する（p は素数）
+      --zeta <K>       k = 1, ..., K について F_{p^k} 上の射影非特異モデルの点の個数 N_k と、
+                       ゼータ関数の分子 L(T) を求める（p は素数、p^K は 2^26 以下）
+      --method <方法>  解き方: table（値の表）, roots（根を求める）, brute（全探索）
+                       省略時は p が 2^26 以下なら table、それより大きければ roots
+                       table は体の元の個数が 2^26 以下の場合だけ使える
+      --bench          ModInt と MontgomeryModInt で解く時間を比べる
+  -h, --help           この説明を表示する
+
+省略した値は標準入力から対話的に入力する。係数は負の値や p 以上の値も可（mod p で簡約する）。";
+
+/// コマンドライン引数や入力値の誤り
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CliError {
+    /// 知らないオプション
+    UnknownOption(String),
+    /// オプションに値が無い
+    MissingValue(String),
+    /// 同じオプションが2回以上与えられた
+    DuplicateOption(String),
+    /// 同時に使えないオプションが与えられた
+    ConflictingOptions(String, String),
+    /// 標数として不正な値
+    InvalidPrime(String),
+    /// 次数として不正な値
+    InvalidDegree(String),
+    /// 解の個数の上限として不正な値
+    InvalidLimit(String),
+    /// 係数として不正な値
+    InvalidCoefficient(String),
+    /// 解き方として不正な値
+    InvalidMethod(String),
+    /// 標数が素数であることが必要な機能
+    RequiresPrime(String),
+    /// 拡大体を作れない
+    InvalidField(String, GaloisFieldError),
+    /// f(x) = g(y) の形の方程式にしか使えない機能
+    RequiresSeparated(String),
+    /// 方程式が1つの場合にしか使えない機能
+    RequiresSingleEquation(String),
+    /// ```--sum```と一緒にしか使えないオプション
+    RequiresSum(String),
+    /// 扱える大きさを超えた
+    TooLarge(String),
+    /// 方程式の式が読めない
+    InvalidEquation(String, ParseError),
+    /// 標準入力が途中で終わった
+    UnexpectedEof,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::UnknownOption(s) => write!(f, "不明なオプション '{}' です", s),
+            CliError::MissingValue(s) => write!(f, "オプション '{}' に値がありません", s),
+            CliError::DuplicateOption(s) => write!(f, "オプション '{}' が重複しています", s),
+            CliError::ConflictingOptions(s, t) => {
+                write!(f, "オプション '{}' と '{}' は同時に使えません", s, t)
+            }
+            CliError::InvalidPrime(s) => {
+                write!(f, "'{}' は標数として不正です（2以上の整数を与えてください）", s)
+            }
+            CliError::InvalidDegree(s) => write!(f, "'{}' は次数として不正です", s),
+            CliError::InvalidLimit(s) => write!(f, "'{}' は解の個数の上限として不正です", s),
+            CliError::InvalidCoefficient(s) => write!(f, "'{}' は係数として不正です", s),
+            CliError::InvalidEquation(s, e) => {
+                write!(f, "式が読めません（{}）\n{}", e, e.show_position(s))
+            }
+            CliError::InvalidMethod(s) => {
+                write!(f, "'{}' は解き方として不正です（table, roots, brute のいずれか）", s)
+            }
+            CliError::InvalidField(s, e) => write!(f, "{} で体を作れません（{}）", s, e),
+            CliError::RequiresSeparated(s) => {
+                write!(f, "{} は f(x) = g(y) の形の方程式にのみ使えます", s)
+            }
+            CliError::RequiresSingleEquation(s) => {
+                write!(f, "{} は方程式が1つの場合にのみ使えます", s)
+            }
+            CliError::RequiresSum(s) => write!(f, "{} は --sum と一緒に使います", s),
+            CliError::TooLarge(s) => write!(f, "{} で扱う体が大きすぎます", s),
+            CliError::RequiresPrime(s) => write!(f, "{} は標数が素数の場合にのみ使えます", s),
+            CliError::UnexpectedEof => write!(f, "入力が途中で終了しました"),
+        }
+    }
+}
+
+/// 解を求める方法
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Method {
+    /// 値の表を作ってファイバーを突き合わせる（```solver::solutions```）
+    Table,
+    /// 各 x について g(y) - f(x) の根を求める（```solver::solutions_by_roots```）
+    Roots,
+    /// 全探索（```solver::solve_equation_brute_force```）
+    BruteForce,
+}
+
+/// コマンドライン引数から読み取った設定。与えられなかったものは```None```。
+///
+/// ```--equation```と```--sum```は複数回与えられるので、与えられた順に```equations```と```sums```に並べる。
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Config {
+    pub prime: Option<u64>,
+    pub extension: Option<usize>,
+    pub modulus: Option<Vec<i128>>,
+    pub f: Option<Vec<i128>>,
+    pub g: Option<Vec<i128>>,
+    pub equations: Vec<String>,
+    pub sums: Vec<Vec<i128>>,
+    pub value: Option<i128>,
+    pub count: bool,
+    pub stream: bool,
+    pub projective: bool,
+    pub singular: bool,
+    pub limit: Option<u64>,
+    pub method: Option<Method>,
+    pub factor: Option<i128>,
+    pub discriminant: bool,
+    pub zeta: Option<usize>,
+    pub bench: bool,
+    pub help: bool,
+}
+
+/// 値をとらないオプション
+const FLAGS: [&str; 9] = [
+    "-h",
+    "--help",
+    "-c",
+    "--count",
+    "--stream",
+    "--projective",
+    "--singular",
+    "--discriminant",
+    "--bench",
+];
+
+/// 値をとるオプション
+const OPTIONS: [&str; 14] = [
+    "-p",
+    "--prime",
+    "--extension",
+    "--modulus",
+    "--f",
+    "--g",
+    "-e",
+    "--equation",
+    "--sum",
+    "--value",
+    "--limit",
+    "--method",
+    "--factor",
+    "--zeta",
+];
+
+/// コマンドライン引数（プログラム名を除く）を解釈する。
+pub fn parse_args(args: &[String]) -> Result<Config, CliError> {
+    let mut config = Config::default();
+    let mut i = 0;
+    while i < args.len() {
+        // ```--name=value```の形も受け付ける
+        let (name, inline_value) = match args[i].find('=') {
+            Some(pos) if args[i].starts_with("--") => {
+                (&args[i][..pos], Some(args[i][pos + 1..].to_string()))
+            }
+            _ => (args[i].as_str(), None),
+        };
+        if FLAGS.contains(&name) && inline_value.is_none() {
+            match name {
+                "-h" | "--help" => config.help = true,
+                "-c" | "--count" => config.count = true,
+                "--stream" => config.stream = true,
+                "--projective" => config.projective = true,
+                "--singular" => config.singular = true,
+                "--discriminant" => config.discriminant = true,
+                _ => config.bench = true,
+            }
+            i += 1;
+            continue;
+        }
+        if !OPTIONS.contains(&name) {
+            return Err(CliError::UnknownOption(args[i].clone()));
+        }
+        let value = match inline_value {
+            Some(v) => v,
+            None => {
+                i += 1;
+                match args.get(i) {
+                    Some(v) => v.clone(),
+                    None => return Err(CliError::MissingValue(name.to_string())),
+                }
+            }
+        };
+        match name {
+            "-p" | "--prime" => set_once(&mut config.prime, name, parse_prime(&value)?)?,
+            "--extension" => set_once(&mut config.extension, name, parse_extension(&value)?)?,
+            "--modulus" => set_once(&mut config.modulus, name, parse_coefficients(&value)?)?,
+            "--f" => set_once(&mut config.f, name, parse_coefficients(&value)?)?,
+            "--g" => set_once(&mut config.g, name, parse_coefficients(&value)?)?,
+            "--sum" => config.sums.push(parse_coefficients(&value)?),
+            "--value" => set_once(&mut config.value, name, parse_coefficient(&value)?)?,
+            "--limit" => set_once(&mut config.limit, name, parse_limit(&value)?)?,
+            "--method" => set_once(&mut config.method, name, parse_method(&value)?)?,
+            "--factor" => set_once(&mut config.factor, name, parse_coefficient(&value)?)?,
+            "--zeta" => set_once(&mut config.zeta, name, parse_extension(&value)?)?,
+            _ => config.equations.push(value),
+        }
+        i += 1;
+    }
+    let has_equation = !config.equations.is_empty();
+    check_conflict(has_equation, "--equation", config.f.is_some(), "--f")?;
+    check_conflict(has_equation, "--equation", config.g.is_some(), "--g")?;
+    let has_sum = !config.sums.is_empty();
+    check_conflict(has_sum, "--sum", has_equation, "--equation")?;
+    check_conflict(has_sum, "--sum", config.f.is_some(), "--f")?;
+    check_conflict(has_sum, "--sum", config.g.is_some(), "--g")?;
+    if config.value.is_some() && !has_sum {
+        return Err(CliError::RequiresSum("--value".to_string()));
+    }
+    check_conflict(config.count, "--count", config.stream, "--stream")?;
+    check_conflict(config.count, "--count", config.limit.is_some(), "--limit")?;
+    check_conflict(config.singular, "--singular", config.stream, "--stream")?;
+    check_conflict(
+        config.extension.is_some(),
+        "--extension",
+        config.modulus.is_some(),
+        "--modulus",
+    )?;
+    check_conflict(config.zeta.is_some(), "--zeta", config.extension.is_some(), "--extension")?;
+    check_conflict(config.zeta.is_some(), "--zeta", config.modulus.is_some(), "--modulus")?;
+    check_conflict(config.factor.is_some(), "--factor", config.count, "--count")?;
+    check_conflict(config.factor.is_some(), "--factor", config.stream, "--stream")?;
+    check_conflict(config.discriminant, "--discriminant", config.count, "--count")?;
+    check_conflict(config.discriminant, "--discriminant", config.stream, "--stream")?;
+    check_conflict(config.discriminant, "--discriminant", config.factor.is_some(), "--factor")?;
+    Ok(config)
+}
+
+/// まだ設定されていなければ値を設定する
+fn set_once<V>(slot: &mut Option<V>, name: &str, value: V) -> Result<(), CliError> {
+    if slot.is_some() {
+        return Err(CliError::DuplicateOption(name.to_string()));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// 同時に使えないオプションが両方与えられていないか確かめる
+fn check_conflict(a: bool, a_name: &str, b: bool, b_name: &str) -> Result<(), CliError> {
+    if a && b {
+        Err(CliError::ConflictingOptions(
+            a_name.to_string(),
+            b_name.to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// 解を求める方法を読む。
+pub fn parse_method(s: &str) -> Result<Method, CliError> {
+    match s.trim() {
+        "table" => Ok(Method::Table),
+        "roots" => Ok(Method::Roots),
+        "brute" => Ok(Method::BruteForce),
+        _ => Err(CliError::InvalidMethod(s.trim().to_string())),
+    }
+}
+
+/// 表示する解の個数の上限を読む。
+pub fn parse_limit(s: &str) -> Result<u64, CliError> {
+    s.trim()
+        .parse::<u64>()
+        .map_err(|_| CliError::InvalidLimit(s.trim().to_string()))
+}
+
+/// 標数を読む。```2```以上の整数のみ受け付ける。
+pub fn parse_prime(s: &str) -> Result<u64, CliError> {
+    match s.trim().parse::<u64>() {
+        Ok(p) if p >= 2 => Ok(p),
+        _ => Err(CliError::InvalidPrime(s.trim().to_string())),
+    }
+}
+
+/// 次数を読む。
+pub fn parse_degree(s: &str) -> Result<usize, CliError> {
+    s.trim()
+        .parse::<usize>()
+        .map_err(|_| CliError::InvalidDegree(s.trim().to_string()))
+}
+
+/// 拡大次数を読む。```1```以上の整数のみ受け付ける。
+pub fn parse_extension(s: &str) -> Result<usize, CliError> {
+    match s.trim().parse::<usize>() {
+        Ok(k) if k >= 1 => Ok(k),
+        _ => Err(CliError::InvalidDegree(s.trim().to_string())),
+    }
+}
+
+/// 係数を1つ読む。負の値も受け付ける。
+pub fn parse_coefficient(s: &str) -> Result<i128, CliError> {
+    s.trim()
+        .parse::<i128>()
+        .map_err(|_| CliError::InvalidCoefficient(s.trim().to_string()))
+}
+
+/// カンマ区切りの係数の列を読む。
+pub fn parse_coefficients(s: &str) -> Result<Vec<i128>, CliError> {
+    s.split(',').map(parse_coefficient).collect()
+}
+
+/// 係数を```0```以上```p```未満に簡約する。
+pub fn reduce(c: i128, p: u64) -> u64 {
+    c.rem_euclid(p as i128) as u64
+}
+
+/// 標準入力から1行読む。入力が終わっていたらエラー。
+pub fn read_line<R: BufRead>(input: &mut R) -> Result<String, CliError> {
+    let mut s = String::new();
+    match input.read_line(&mut s) {
+        Ok(0) | Err(_) => Err(CliError::UnexpectedEof),
+        Ok(_) => Ok(s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Config, CliError> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        parse_args(&args)
+    }
+
+    fn conflict(a: &str, b: &str) -> Result<Config, CliError> {
+        Err(CliError::ConflictingOptions(a.to_string(), b.to_string()))
+    }
+
+    #[test]
+    fn parses_options() {
+        let config = parse(&["-p", "7", "--f=1,-2,3", "--g", "0, 1", "-h"]).unwrap();
+        assert_eq!(config.prime, Some(7));
+        assert_eq!(config.f, Some(vec![1, -2, 3]));
+        assert_eq!(config.g, Some(vec![0, 1]));
+        assert!(config.help);
+        assert_eq!(parse(&[]), Ok(Config::default()));
+    }
+
+    #[test]
+    fn rejects_missing_duplicate_and_unknown_options() {
+        assert_eq!(
+            parse(&["-p"]),
+            Err(CliError::MissingValue("-p".to_string()))
+        );
+        assert_eq!(
+            parse(&["-p", "5", "--f"]),
+            Err(CliError::MissingValue("--f".to_string()))
+        );
+        assert_eq!(
+            parse(&["-p", "5", "--prime=7"]),
+            Err(CliError::DuplicateOption("--prime".to_string()))
+        );
+        assert_eq!(
+            parse(&["-x"]),
+            Err(CliError::UnknownOption("-x".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        for s in &["0", "1", "-7", "abc", ""] {
+            assert_eq!(
+                parse(&["-p", s]),
+                Err(CliError::InvalidPrime(s.to_string()))
+            );
+        }
+        // 素数でない p は環 Z/pZ として受け付ける（拡大体などの可否は実行時に判定する）
+        assert_eq!(parse(&["-p", "6"]).unwrap().prime, Some(6));
+        assert_eq!(
+            parse(&["--f", "1,x"]),
+            Err(CliError::InvalidCoefficient("x".to_string()))
+        );
+        assert_eq!(
+            parse_degree("-1"),
+            Err(CliError::InvalidDegree("-1".to_string()))
+        );
+        assert_eq!(reduce(-1, 7), 6);
+        assert_eq!(reduce(15, 7), 1);
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(
+            CliError::UnknownOption("-x".to_string()).to_string(),
+            "不明なオプション '-x' です"
+        );
+        assert_eq!(
+            CliError::MissingValue("-p".to_string()).to_string(),
+            "オプション '-p' に値がありません"
+        );
+        assert_eq!(
+            CliError::DuplicateOption("--f".to_string()).to_string(),
+            "オプション '--f' が重複しています"
+        );
+        assert_eq!(
+            CliError::InvalidPrime("1".to_string()).to_string(),
+            "'1' は標数として不正です（2以上の整数を与えてください）"
+        );
+        assert_eq!(
+            CliError::UnexpectedEof.to_string(),
+            "入力が途中で終了しました"
+        );
+    }
+
+    #[test]
+    fn read_line_reports_eof() {
+        let mut input: &[u8] = b"7\n";
+        assert_eq!(read_line(&mut input), Ok("7\n".to_string()));
+        assert_eq!(read_line(&mut input), Err(CliError::UnexpectedEof));
+    }
+
+    #[test]
+    fn parses_equations() {
+        let config = parse(&["-e", "x = y", "--equation", "x^2 = y"]).unwrap();
+        assert_eq!(config.equations, vec!["x = y", "x^2 = y"]);
+        assert_eq!(
+            parse(&["-e", "x = y", "--f", "1"]),
+            conflict("--equation", "--f")
+        );
+    }
+
+    #[test]
+    fn parses_count() {
+        assert!(parse(&["-c"]).unwrap().count);
+        assert!(parse(&["--count"]).unwrap().count);
+    }
+
+    #[test]
+    fn parses_limit_and_stream() {
+        let config = parse(&["--limit", "5", "--stream"]).unwrap();
+        assert_eq!(config.limit, Some(5));
+        assert!(config.stream);
+        assert_eq!(
+            parse(&["--count", "--stream"]),
+            conflict("--count", "--stream")
+        );
+        assert_eq!(
+            parse(&["-c", "--limit", "3"]),
+            conflict("--count", "--limit")
+        );
+        assert_eq!(
+            parse(&["--limit", "-1"]),
+            Err(CliError::InvalidLimit("-1".to_string()))
+        );
+        // 値をとらないオプションに値を与えることはできない
+        assert_eq!(
+            parse(&["--count=1"]),
+            Err(CliError::UnknownOption("--count=1".to_string()))
+        );
+        assert_eq!(
+            conflict("--count", "--stream").unwrap_err().to_string(),
+            "オプション '--count' と '--stream' は同時に使えません"
+        );
+    }
+
+    #[test]
+    fn parses_method() {
+        let config = parse(&["-p", "7", "-c", "--method", "roots"]).unwrap();
+        assert_eq!(config.method, Some(Method::Roots));
+        assert_eq!(
+            parse(&["--method", "fast"]),
+            Err(CliError::InvalidMethod("fast".to_string()))
+        );
+        assert_eq!(parse_method(" brute "), Ok(Method::BruteForce));
+        assert_eq!(
+            CliError::InvalidMethod("fast".to_string()).to_string(),
+            "'fast' は解き方として不正です（table, roots, brute のいずれか）"
+        );
+        assert_eq!(
+            CliError::RequiresPrime("--method roots".to_string()).to_string(),
+            "--method roots は標数が素数の場合にのみ使えます"
+        );
+        assert_eq!(
+            CliError::TooLarge("--count".to_string()).to_string(),
+            "--count で扱う体が大きすぎます"
+        );
+    }
+
+    #[test]
+    fn factor_conflicts_with_count() {
+        assert_eq!(
+            parse(&["--factor", "0", "-c"]),
+            conflict("--factor", "--count")
+        );
+    }
+
+    #[test]
+    fn parses_extension() {
+        assert_eq!(parse(&["--extension", "2"]).unwrap().extension, Some(2));
+        assert_eq!(
+            parse(&["--extension", "0"]),
+            Err(CliError::InvalidDegree("0".to_string()))
+        );
+        assert_eq!(
+            parse(&["--extension", "2", "--modulus", "1,1,1"]),
+            conflict("--extension", "--modulus")
+        );
+    }
+
+    #[test]
+    fn zeta_conflicts_with_extension() {
+        assert_eq!(
+            parse(&["--zeta", "3", "--extension", "2"]),
+            conflict("--zeta", "--extension")
+        );
+    }
+
+    #[test]
+    fn singular_conflicts_with_stream() {
+        assert_eq!(
+            parse(&["--singular", "--stream"]),
+            conflict("--singular", "--stream")
+        );
+    }
+
+    #[test]
+    fn discriminant_conflicts_with_factor() {
+        assert_eq!(
+            parse(&["--discriminant", "--factor", "1"]),
+            conflict("--discriminant", "--factor")
+        );
+    }
+
+    #[test]
+    fn parses_sums() {
+        let config = parse(&["--sum", "0,0,1", "--sum", "1", "--value", "-1"]).unwrap();
+        assert_eq!(config.sums, vec![vec![0, 0, 1], vec![1]]);
+        assert_eq!(config.value, Some(-1));
+        assert_eq!(
+            parse(&["--sum", "1", "-e", "x = y"]),
+            conflict("--sum", "--equation")
+        );
+        assert_eq!(
+            parse(&["--value", "1"]),
+            Err(CliError::RequiresSum("--value".to_string()))
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::identities::Zero;
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+/// これより短い列どうしの積は筆算で求める
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// 係数列の積（畳み込み）を計算できる型。多項式の掛け算に使う。
+///
+/// 既定の実装は、短い列なら筆算、長い列なら Karatsuba 法で計算する。
+/// 剰余環の元などは、長い列に対して数論変換（NTT）を使う実装で上書きする。
+pub trait Convolution:
+    Sized + Copy + Zero + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + AddAssign
+{
+    /// ```c[k] = Σ_{i + j = k} a[i] * b[j]```となる長さ```a.len() + b.len() - 1```の列を返す。
+    /// どちらかが空なら空の列を返す。
+    fn convolve(a: &[Self], b: &[Self]) -> Vec<Self> {
+        karatsuba(a, b)
+    }
+}
+
+/// 筆算による積。計算量は O(nm)。
+pub fn schoolbook<T: Convolution>(a: &[T], b: &[T]) -> Vec<T> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut c = vec![T::zero(); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            c[i + j] += x * y;
+        }
+    }
+    c
+}
+
+/// Karatsuba 法による積。計算量は O(n^1.59)。短い列は筆算に任せる。
+pub fn karatsuba<T: Convolution>(a: &[T], b: &[T]) -> Vec<T> {
+    if a.len().min(b.len()) <= KARATSUBA_THRESHOLD {
+        return schoolbook(a, b);
+    }
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut c = vec![T::zero(); a.len() + b.len() - 1];
+
+    // 長さが大きく違う場合は、長い方を短い方の長さごとに区切って掛ける
+    if long.len() >= 2 * short.len() {
+        for (k, chunk) in long.chunks(short.len()).enumerate() {
+            let offset = k * short.len();
+            for (i, z) in karatsuba(chunk, short).into_iter().enumerate() {
+                c[offset + i] += z;
+            }
+        }
+        return c;
+    }
+
+    // long = l0 + l1 x^h, short = s0 + s1 x^h と分けて
+    // (l0 + l1)(s0 + s1) - l0 s0 - l1 s1 で交差項を求める
+    let h = long.len() / 2;
+    let (l0, l1) = long.split_at(h);
+    let (s0, s1) = short.split_at(h.min(short.len()));
+    let z0 = karatsuba(l0, s0);
+    let z2 = karatsuba(l1, s1);
+    let z1 = karatsuba(&add_slices(l0, l1), &add_slices(s0, s1));
+
+    for (i, &z) in z0.iter().enumerate() {
+        c[i] += z;
+    }
+    for (i, &z) in z2.iter().enumerate() {
+        c[2 * h + i] += z;
+    }
+    for (i, &z) in z1.iter().enumerate() {
+        let mut w = z;
+        if let Some(&z) = z0.get(i) {
+            w = w - z;
+        }
+        if let Some(&z) = z2.get(i) {
+            w = w - z;
+        }
+        if h + i < c.len() {
+            c[h + i] += w;
+        }
+    }
+    c
+}
+
+/// 係数ごとの和。長さが違う場合は長い方に合わせる。
+fn add_slices<T: Convolution>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut c = vec![T::zero(); a.len().max(b.len())];
+    for (i, &x) in a.iter().enumerate() {
+        c[i] += x;
+    }
+    for (i, &y) in b.iter().enumerate() {
+        c[i] += y;
+    }
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modint::{ModInt, BIG};
+    use crate::random::Random;
+
+    fn random_vec(n: usize, random: &mut Random) -> Vec<ModInt<BIG>> {
+        (0..n).map(|_| ModInt::new(random.next_u64())).collect()
+    }
+
+    #[test]
+    fn karatsuba_matches_schoolbook() {
+        let mut random = Random::default();
+        let threshold = KARATSUBA_THRESHOLD;
+        let lengths = [
+            (0, 5),
+            (1, 1),
+            (threshold, threshold),
+            (threshold + 1, threshold + 1),
+            (100, 100),
+            (100, 99),
+            (257, 130),
+            (300, threshold + 1),
+            (1000, 40),
+        ];
+        for &(n, k) in &lengths {
+            let a = random_vec(n, &mut random);
+            let b = random_vec(k, &mut random);
+            assert_eq!(
+                karatsuba(&a, &b),
+                schoolbook(&a, &b),
+                "n = {}, k = {}",
+                n,
+                k
+            );
+            assert_eq!(
+                karatsuba(&b, &a),
+                schoolbook(&a, &b),
+                "n = {}, k = {}",
+                k,
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn karatsuba_with_u64_max_modulus() {
+        // 合成数の法でも、掛け算と足し算だけなので結果は変わらない
+        let mut random = Random::default();
+        let a: Vec<ModInt<{ u64::MAX }>> =
+            (0..200).map(|_| ModInt::new(random.next_u64())).collect();
+        let b: Vec<ModInt<{ u64::MAX }>> =
+            (0..150).map(|_| ModInt::new(random.next_u64())).collect();
+        assert_eq!(karatsuba(&a, &b), schoolbook(&a, &b));
+    }
+}
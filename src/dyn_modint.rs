@@ -0,0 +1,262 @@
+use crate::finite_ring::{FiniteField, FiniteRing};
+use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
+use crate::modint::{add_mod, inverse_mod, mul_mod, sub_mod};
+use std::cell::Cell;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+thread_local! {
+    /// 現在の法。```DynModContext```が生きている間だけ設定される。
+    static MODULUS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// ```DynModInt```の法を実行時に設定するためのコンテキスト。
+///
+/// 生成すると（そのスレッドで）法が設定され、破棄すると元の法に戻る。
+pub struct DynModContext {
+    modulus: u64,
+    previous: u64,
+}
+
+impl DynModContext {
+    /// コンストラクタ。法```modulus```は```1```以上とする。
+    pub fn new(modulus: u64) -> Self {
+        assert!(modulus > 0, "法は1以上である必要があります");
+        let previous = MODULUS.with(|m| m.replace(modulus));
+        DynModContext { modulus, previous }
+    }
+
+    /// 設定している法を返す
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+}
+
+impl Drop for DynModContext {
+    fn drop(&mut self) {
+        MODULUS.with(|m| m.set(self.previous));
+    }
+}
+
+/// 実行時に与えた法で割った余り。法は```DynModContext```から読み出す。
+///
+/// 使い方は```ModInt```と同じで、```Polynomial```の係数などに使える。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DynModInt {
+    representative: u64,
+}
+
+impl DynModInt {
+    /// 現在の法を返す。```DynModContext```が無い場合はパニックする。
+    pub fn modulus() -> u64 {
+        let m = MODULUS.with(|m| m.get());
+        assert!(m > 0, "DynModContext で法が設定されていません");
+        m
+    }
+
+    /// コンストラクタ。代表元が一意になるよう```0```以上法未満の整数として格納。
+    pub fn new(n: u64) -> Self {
+        DynModInt {
+            representative: n % Self::modulus(),
+        }
+    }
+
+    /// ```u64```型にする
+    pub fn to_int(&self) -> u64 {
+        self.representative
+    }
+
+    /// 繰り返し二乗法によるべき乗の計算
+    pub fn modpow(&self, n: u64) -> Self {
+        let modulus = Self::modulus();
+        let mut res = 1 % modulus;
+        let mut a = self.representative;
+        let mut m = n;
+        loop {
+            if m == 0 {
+                break;
+            }
+            if m % 2 == 1 {
+                res = mul_mod(res, a, modulus);
+            }
+            a = mul_mod(a, a, modulus);
+            m /= 2;
+        }
+        DynModInt {
+            representative: res,
+        }
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl fmt::Display for DynModInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.representative)
+    }
+}
+
+impl Add for DynModInt {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        DynModInt {
+            representative: add_mod(self.representative, rhs.representative, Self::modulus()),
+        }
+    }
+}
+
+impl AddAssign for DynModInt {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for DynModInt {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        DynModInt {
+            representative: sub_mod(self.representative, rhs.representative, Self::modulus()),
+        }
+    }
+}
+
+impl SubAssign for DynModInt {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Mul for DynModInt {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        DynModInt {
+            representative: mul_mod(self.representative, rhs.representative, Self::modulus()),
+        }
+    }
+}
+
+impl MulAssign for DynModInt {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+/// unary negation の実装
+impl Neg for DynModInt {
+    type Output = Self;
+    fn neg(self) -> Self {
+        DynModInt::new(Self::modulus() - self.representative)
+    }
+}
+
+impl Zero for DynModInt {
+    fn zero() -> Self {
+        DynModInt::new(0)
+    }
+}
+
+impl Identity for DynModInt {
+    fn identity() -> Self {
+        DynModInt::new(1)
+    }
+}
+
+/// 拡張ユークリッドの互除法で求めるので、法が合成数でも正しい逆元を返す。
+impl Inverse for DynModInt {
+    fn inverse(self) -> Option<DynModInt> {
+        inverse_mod(self.representative, Self::modulus()).map(|n| DynModInt { representative: n })
+    }
+}
+
+impl FiniteRing for DynModInt {
+    fn cardinality() -> u64 {
+        Self::modulus()
+    }
+
+    fn element(i: u64) -> Self {
+        DynModInt::new(i)
+    }
+
+    fn index(&self) -> u64 {
+        self.representative
+    }
+}
+
+/// 法が素数の場合に限り有限体になる。
+impl FiniteField for DynModInt {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_matches_integers() {
+        for &m in &[2, 3, 7, 12, 101] {
+            let _context = DynModContext::new(m);
+            for a in 0..m {
+                let x = DynModInt::new(a);
+                assert_eq!((-x).to_int(), (m - a) % m);
+                assert_eq!(x.modpow(3).to_int(), a * a * a % m);
+                for b in 0..m {
+                    let y = DynModInt::new(b);
+                    assert_eq!((x + y).to_int(), (a + b) % m);
+                    assert_eq!((x - y).to_int(), (a + m - b) % m);
+                    assert_eq!((x * y).to_int(), a * b % m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inverses_for_prime_moduli() {
+        for &p in &[2, 3, 7, 101] {
+            let _context = DynModContext::new(p);
+            assert_eq!(DynModInt::zero().inverse(), None);
+            for n in 1..p {
+                let a = DynModInt::new(n);
+                assert_eq!(a * a.inverse().unwrap(), DynModInt::identity());
+            }
+        }
+    }
+
+    #[test]
+    fn elements_are_numbered_by_representative() {
+        let context = DynModContext::new(11);
+        assert_eq!(context.modulus(), 11);
+        assert_eq!(DynModInt::cardinality(), 11);
+        for i in 0..11 {
+            assert_eq!(DynModInt::element(i).index(), i);
+        }
+        assert_eq!(DynModInt::new(25), DynModInt::new(3));
+        assert_eq!(DynModInt::new(25).to_string(), "3");
+    }
+
+    #[test]
+    fn inverses_for_composite_moduli() {
+        for &m in &[1, 6, 8, 9, 15, 210, 561, 2047, 4096] {
+            let _context = DynModContext::new(m);
+            for n in 0..m {
+                let a = DynModInt::new(n);
+                match a.inverse() {
+                    Some(b) => assert_eq!(a * b, DynModInt::identity(), "{} mod {}", n, m),
+                    None => assert_ne!(num::Integer::gcd(&n, &m), 1, "{} mod {}", n, m),
+                }
+                if num::Integer::gcd(&n, &m) == 1 {
+                    assert!(a.inverse().is_some(), "{} mod {}", n, m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn context_restores_previous_modulus() {
+        let _outer = DynModContext::new(7);
+        {
+            let _inner = DynModContext::new(12);
+            assert_eq!(DynModInt::modulus(), 12);
+            assert_eq!(DynModInt::new(5).inverse(), Some(DynModInt::new(5)));
+        }
+        assert_eq!(DynModInt::modulus(), 7);
+        assert_eq!(DynModInt::new(5).inverse(), Some(DynModInt::new(3)));
+    }
+}
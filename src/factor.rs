@@ -0,0 +1,469 @@
+use crate::convolution::Convolution;
+use crate::finite_ring::FiniteField;
+use crate::identities::Identity;
+use crate::polynomial::Polynomial;
+use crate::random::Random;
+
+/// 係数体は有限体 F_q とする。
+impl<T: FiniteField + Convolution + Identity + Eq + Ord> Polynomial<T> {
+    /// 因数分解。モニックな既約因子とその重複度の組を、次数の小さい順に並べて返す。
+    ///
+    /// 最高次係数（定数倍）は含めない。零多項式や定数の場合は空。
+    /// 無平方分解、次数別分解、Cantor–Zassenhaus 法による同次数分解の順に行う。
+    pub fn factor(&self) -> Vec<(Self, usize)> {
+        let mut factors: Vec<(Self, usize)> = Vec::new();
+        let mut random = Random::default();
+        for (g, m) in self.square_free_factorization() {
+            for (h, d) in g.distinct_degree_factorization() {
+                for e in h.equal_degree_factorization(d, &mut random) {
+                    factors.push((e, m));
+                }
+            }
+        }
+        sort_factors(&mut factors);
+        factors
+    }
+
+    /// 無平方分解。互いに素な無平方のモニック多項式 g_i と重複度 i の組で、
+    /// f = (定数) Π g_i^i となるものを返す。零多項式や定数の場合は空。
+    ///
+    /// 標数 p では f' = 0 となることがあるので、そのときは係数の p 乗根をとって
+    /// f = h(x)^p の h に帰着する。
+    pub fn square_free_factorization(&self) -> Vec<(Self, usize)> {
+        if self.is_zero() || self.deg() == 0 {
+            return Vec::new();
+        }
+        let mut result: Vec<(Self, usize)> = Vec::new();
+        let f = self.monic();
+        let one = Self::identity();
+        let mut c = Self::gcd(&f, &Self::derivative(&f));
+        let mut w = f.exact_div(&c).unwrap();
+        let mut i = 1;
+        // w は重複度が p の倍数でない因子の積
+        while w != one {
+            let y = Self::gcd(&w, &c);
+            let z = w.exact_div(&y).unwrap();
+            if z != one {
+                result.push((z, i));
+            }
+            c = c.exact_div(&y).unwrap();
+            w = y;
+            i += 1;
+        }
+        // 残りは重複度が p の倍数の因子で、c = h(x^p) の形をしている
+        if c != one {
+            let p = T::characteristic() as usize;
+            let h: Vec<T> = c
+                .coefficients()
+                .iter()
+                .step_by(p)
+                .map(|&a| a.pth_root())
+                .collect();
+            for (g, m) in Polynomial::new(&h).square_free_factorization() {
+                result.push((g, m * p));
+            }
+        }
+        sort_factors(&mut result);
+        result
+    }
+
+    /// 次数別分解。無平方なモニック多項式を、次数 d の既約因子をすべて掛けたものと d の組に分ける。
+    ///
+    /// x^(q^d) - x が次数 d を割り切る次数の既約多項式すべての積であることを使う。
+    pub fn distinct_degree_factorization(&self) -> Vec<(Self, usize)> {
+        let mut result = Vec::new();
+        if self.is_zero() || self.deg() == 0 {
+            return result;
+        }
+        let q = T::cardinality();
+        let x = Self::x();
+        let mut f = self.monic();
+        let mut h = x.clone();
+        let mut d = 1;
+        while f.deg() >= 2 * d {
+            // h = x^(q^d) mod f
+            h = h.pow_mod(q, &f);
+            let g = Self::gcd(&f, &(h.clone() - x.clone()));
+            if g.deg() > 0 {
+                f = f.exact_div(&g).unwrap();
+                h = h % f.clone();
+                result.push((g, d));
+            }
+            d += 1;
+        }
+        if f.deg() > 0 {
+            let d = f.deg();
+            result.push((f, d));
+        }
+        result
+    }
+
+    /// 既約かどうか（Rabin の判定法）。零多項式と定数は既約でないとする。
+    ///
+    /// 次数 n の f が既約であることは、x^(q^n) ≡ x (mod f) かつ
+    /// n の各素因数 r について gcd(x^(q^(n/r)) - x, f) = 1 であることと同値。
+    pub fn is_irreducible(&self) -> bool {
+        if self.is_zero() || self.deg() == 0 {
+            return false;
+        }
+        let n = self.deg();
+        if n == 1 {
+            return true;
+        }
+        let q = T::cardinality();
+        let f = self.monic();
+        let x = Self::x();
+        let one = Self::identity();
+        let divisors: Vec<usize> = prime_divisors(n).iter().map(|&r| n / r).collect();
+        // h = x^(q^k) mod f
+        let mut h = x.clone();
+        for k in 1..=n {
+            h = h.pow_mod(q, &f);
+            if divisors.contains(&k) && Self::gcd(&f, &(h.clone() - x.clone())) != one {
+                return false;
+            }
+        }
+        h == x
+    }
+
+    /// 次数```n```（```1```以上）のモニックな既約多項式をランダムに1つ求める。
+    ///
+    /// 次数 n のモニック多項式のうち既約なものはおよそ 1/n の割合なので、平均 n 回程度の試行で見つかる。
+    pub fn random_irreducible(n: usize, random: &mut Random) -> Self {
+        assert!(n >= 1, "既約多項式の次数は1以上です");
+        loop {
+            let mut v: Vec<T> = random_polynomial(n, random).coefficients().to_vec();
+            v.resize(n, T::zero());
+            v.push(T::identity());
+            let f = Polynomial::new(&v);
+            if f.is_irreducible() {
+                return f;
+            }
+        }
+    }
+
+    /// 同次数分解（Cantor–Zassenhaus 法）。次数 d の相異なるモニック既約多項式の積を分解する。
+    pub fn equal_degree_factorization(&self, d: usize, random: &mut Random) -> Vec<Self> {
+        let f = self.monic();
+        if f.deg() == 0 {
+            return Vec::new();
+        }
+        if f.deg() == d {
+            return vec![f];
+        }
+        let g = loop {
+            let a = random_polynomial(f.deg(), random);
+            let g = Self::gcd(&f, &equal_degree_splitter(&a, d, &f));
+            if g.deg() > 0 && g.deg() < f.deg() {
+                break g;
+            }
+        };
+        let h = f.exact_div(&g).unwrap();
+        let mut result = g.equal_degree_factorization(d, random);
+        result.extend(h.equal_degree_factorization(d, random));
+        result
+    }
+}
+
+/// 次数が```n```未満のランダムな多項式
+fn random_polynomial<T>(n: usize, random: &mut Random) -> Polynomial<T>
+where
+    T: FiniteField + Convolution + Identity + Eq + Ord,
+{
+    let v: Vec<T> = (0..n)
+        .map(|_| T::element(random.below(T::cardinality())))
+        .collect();
+    Polynomial::new(&v)
+}
+
+/// 次数 d の既約因子たちに対して、確率 1/2 程度でそれぞれ 0 か非零かが分かれる多項式を
+/// ```f```を法として求める。
+///
+/// q が奇数なら a^((q^d - 1) / 2) - 1、q = 2^k なら Σ_{i < kd} a^(2^i) とする。
+/// q^d は```u64```に収まらないことがあるので、(q^d - 1) / 2 = (1 + q + ... + q^(d-1)) (q - 1) / 2
+/// と分けて計算する。
+fn equal_degree_splitter<T>(a: &Polynomial<T>, d: usize, f: &Polynomial<T>) -> Polynomial<T>
+where
+    T: FiniteField + Convolution + Identity + Eq + Ord,
+{
+    let q = T::cardinality();
+    if q % 2 == 1 {
+        // b = a^(1 + q + ... + q^(d-1))
+        let mut power = a.clone() % f.clone();
+        let mut b = power.clone();
+        for _ in 1..d {
+            power = power.pow_mod(q, f);
+            b = (b * power.clone()) % f.clone();
+        }
+        b.pow_mod((q - 1) / 2, f) - Polynomial::identity()
+    } else {
+        let k = q.trailing_zeros() as usize;
+        let mut power = a.clone() % f.clone();
+        let mut trace = power.clone();
+        for _ in 1..(k * d) {
+            power = (power.clone() * power) % f.clone();
+            trace += power.clone();
+        }
+        trace
+    }
+}
+
+/// ```n```の相異なる素因数
+fn prime_divisors(mut n: usize) -> Vec<usize> {
+    let mut result = Vec::new();
+    let mut r = 2;
+    while r * r <= n {
+        if n.is_multiple_of(r) {
+            result.push(r);
+            while n.is_multiple_of(r) {
+                n /= r;
+            }
+        }
+        r += 1;
+    }
+    if n > 1 {
+        result.push(n);
+    }
+    result
+}
+
+/// 因子を次数、係数の順で並べ、同じ因子は重複度をまとめる
+fn sort_factors<T: Copy + Ord>(factors: &mut Vec<(Polynomial<T>, usize)>) {
+    factors.sort_by(|a, b| {
+        (a.0.deg(), a.0.coefficients()).cmp(&(b.0.deg(), b.0.coefficients()))
+    });
+    factors.dedup_by(|a, b| {
+        if a.0.coefficients() == b.0.coefficients() {
+            b.1 += a.1;
+            true
+        } else {
+            false
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::galois_field::{GaloisField, GfElem};
+    use crate::identities::Zero;
+    use crate::testing::polynomial;
+
+    /// 因子を掛け戻すと元の多項式のモニック化に戻ることと、因子がモニックで次数順に並ぶことを確かめる
+    fn check_round_trip<T: FiniteField + Convolution + Identity + Eq + Ord + std::fmt::Debug>(
+        f: &Polynomial<T>,
+    ) -> Vec<(Polynomial<T>, usize)> {
+        let factors = f.factor();
+        let mut product = Polynomial::identity();
+        for (g, m) in &factors {
+            assert!(g.deg() > 0 && g.leading_coefficient() == T::identity() && *m > 0);
+            for _ in 0..*m {
+                product *= g.clone();
+            }
+        }
+        assert_eq!(product, f.monic());
+        assert!(factors
+            .windows(2)
+            .all(|w| w[0].0.deg() <= w[1].0.deg() && w[0].0 != w[1].0));
+        factors
+    }
+
+    #[test]
+    fn factor_known_polynomial() {
+        let _context = DynModContext::new(3);
+        // x + 1, x^2 + 1, x^3 + 2x + 1 は F_3 上既約
+        let (a, b, c) = (
+            polynomial(&[1, 1]),
+            polynomial(&[1, 0, 1]),
+            polynomial(&[1, 2, 0, 1]),
+        );
+        let f = a.clone()
+            * a.clone()
+            * a.clone()
+            * b.clone()
+            * b.clone()
+            * c.clone()
+            * Polynomial::new_constant(DynModInt::new(2));
+        assert_eq!(check_round_trip(&f), vec![(a, 3), (b, 2), (c, 1)]);
+    }
+
+    #[test]
+    fn factor_p_th_power() {
+        // 標数 3 で (x^3 + 2x + 1)^3 の微分は 0 になる
+        let _context = DynModContext::new(3);
+        let c = polynomial::<DynModInt>(&[1, 2, 0, 1]);
+        let f = c.clone() * c.clone() * c.clone();
+        assert_eq!(Polynomial::derivative(&f), Polynomial::zero());
+        assert_eq!(check_round_trip(&f), vec![(c, 3)]);
+    }
+
+    #[test]
+    fn factor_round_trip() {
+        let mut random = Random::new(5);
+        for &p in &[2, 3, 5, 7, 101, 1_000_000_007] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                // 重複度を持つように、ランダムな多項式の積にする
+                let g = random_polynomial::<DynModInt>(1 + random.below(6) as usize, &mut random);
+                let h = random_polynomial::<DynModInt>(1 + random.below(4) as usize, &mut random);
+                let f = g.clone() * g * h;
+                if f.is_zero() {
+                    continue;
+                }
+                check_round_trip(&f);
+            }
+        }
+        let _field = GaloisField::with_degree(2, 3).unwrap();
+        for _ in 0..20 {
+            let g = random_polynomial::<GfElem>(1 + random.below(5) as usize, &mut random);
+            let f = g.clone() * g.clone() * g;
+            if !f.is_zero() {
+                check_round_trip(&f);
+            }
+        }
+    }
+
+    #[test]
+    fn square_free_factorization_round_trip() {
+        let mut random = Random::new(6);
+        for &p in &[2, 3, 5] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                let g = random_polynomial::<DynModInt>(1 + random.below(4) as usize, &mut random);
+                let h = random_polynomial::<DynModInt>(1 + random.below(4) as usize, &mut random);
+                let mut f = g.clone() * g.clone() * h;
+                for _ in 0..p {
+                    f *= g.clone();
+                }
+                if f.is_zero() {
+                    continue;
+                }
+                let mut product = Polynomial::identity();
+                for (s, i) in f.square_free_factorization() {
+                    let d = Polynomial::derivative(&s);
+                    assert_eq!(
+                        Polynomial::gcd(&s, &d),
+                        Polynomial::identity(),
+                        "{} mod {}",
+                        s,
+                        p
+                    );
+                    for _ in 0..i {
+                        product *= s.clone();
+                    }
+                }
+                assert_eq!(product, f.monic(), "{} mod {}", f, p);
+            }
+        }
+    }
+
+    /// 次数 n のモニック既約多項式の個数 (1/n) Σ_{d | n} μ(d) p^(n/d)
+    fn irreducible_count(p: i64, n: u32) -> i64 {
+        let mobius = |d: u32| {
+            let mut d = d;
+            let mut sign = 1;
+            let mut r = 2;
+            while d > 1 {
+                if d.is_multiple_of(r) {
+                    d /= r;
+                    if d.is_multiple_of(r) {
+                        return 0;
+                    }
+                    sign = -sign;
+                }
+                r += 1;
+            }
+            sign
+        };
+        (1..=n)
+            .filter(|&d| n.is_multiple_of(d))
+            .map(|d| mobius(d) * p.pow(n / d))
+            .sum::<i64>()
+            / n as i64
+    }
+
+    #[test]
+    fn count_monic_irreducibles() {
+        for &(p, n_max) in &[(2, 6), (3, 4), (5, 3)] {
+            let _context = DynModContext::new(p);
+            for n in 1..=n_max {
+                let mut count = 0;
+                for i in 0..p.pow(n as u32) {
+                    let mut v: Vec<u64> = (0..n).map(|k| i / p.pow(k as u32) % p).collect();
+                    v.push(1);
+                    let f = polynomial::<DynModInt>(&v);
+                    let irreducible = f.is_irreducible();
+                    // 因数分解の結果とも一致する
+                    assert_eq!(
+                        irreducible,
+                        f.factor() == vec![(f.clone(), 1)],
+                        "{} mod {}",
+                        f,
+                        p
+                    );
+                    if irreducible {
+                        count += 1;
+                    }
+                }
+                assert_eq!(
+                    count,
+                    irreducible_count(p as i64, n as u32),
+                    "n = {} mod {}",
+                    n,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rabin_test_on_known_polynomials() {
+        let irreducible = |v: &[u64]| polynomial::<DynModInt>(v).is_irreducible();
+        let _context = DynModContext::new(2);
+        // x^8 + x^4 + x^3 + x + 1（AES の既約多項式）
+        assert!(irreducible(&[1, 1, 0, 1, 1, 0, 0, 0, 1]));
+        // x^4 + x^2 + 1 = (x^2 + x + 1)^2
+        assert!(!irreducible(&[1, 0, 1, 0, 1]));
+        // x^6 + x + 1 は既約だが、x^6 + x^5 + x^4 + x^3 + 1 = (x^2 + x + 1)(x^4 + x + 1) は可約で根を持たない
+        assert!(irreducible(&[1, 1, 0, 0, 0, 0, 1]));
+        assert!(!irreducible(&[1, 0, 0, 1, 1, 1, 1]));
+        assert!(!Polynomial::<DynModInt>::zero().is_irreducible());
+        assert!(!irreducible(&[1]));
+
+        let _context = DynModContext::new(1_000_000_007);
+        // x^2 + 1 は p ≡ 3 (mod 4) で既約、p ≡ 1 (mod 4) では可約
+        assert!(irreducible(&[1, 0, 1]));
+        let _context = DynModContext::new(998_244_353);
+        assert!(!irreducible(&[1, 0, 1]));
+    }
+
+    #[test]
+    fn random_irreducible_is_monic_and_irreducible() {
+        let mut random = Random::new(7);
+        for &p in &[2, 3, 1_000_000_007] {
+            let _context = DynModContext::new(p);
+            for n in 1..=8 {
+                let f = Polynomial::<DynModInt>::random_irreducible(n, &mut random);
+                assert_eq!(f.deg(), n);
+                assert_eq!(f.leading_coefficient(), DynModInt::identity());
+                assert!(f.is_irreducible());
+                assert_eq!(f.factor(), vec![(f.clone(), 1)]);
+            }
+        }
+    }
+
+    #[test]
+    fn factors_are_irreducible() {
+        let mut random = Random::new(8);
+        for &p in &[2, 3, 7] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                let f = random_polynomial::<DynModInt>(2 + random.below(10) as usize, &mut random);
+                for (g, _) in f.factor() {
+                    assert!(g.is_irreducible(), "{} | {} mod {}", g, f, p);
+                }
+            }
+        }
+    }
+}
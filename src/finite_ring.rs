@@ -0,0 +1,30 @@
+use crate::inverse::Inverse;
+
+/// 有限環。元に```0```以上```cardinality()```未満の番号を振って列挙できるようにする。
+pub trait FiniteRing
+where
+    Self: std::marker::Sized,
+{
+    /// 元の個数
+    fn cardinality() -> u64;
+
+    /// ```i```番目の元。```i```は```cardinality()```未満とする。
+    fn element(i: u64) -> Self;
+
+    /// 元の番号。```element```の逆。
+    fn index(&self) -> u64;
+}
+
+/// 有限体。零でない元はすべて```Inverse```で逆元をもつとする。
+pub trait FiniteField: FiniteRing + Inverse {
+    /// 標数。既定では素体として元の個数を返す。
+    fn characteristic() -> u64 {
+        Self::cardinality()
+    }
+
+    /// p 乗根（p は標数）。有限体ではフロベニウス写像が全単射なので一意に定まる。
+    /// 既定では素体として自分自身を返す。
+    fn pth_root(self) -> Self {
+        self
+    }
+}
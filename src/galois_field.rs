@@ -0,0 +1,565 @@
+use crate::convolution::Convolution;
+use crate::dyn_modint::{DynModContext, DynModInt};
+use crate::finite_ring::{FiniteField, FiniteRing};
+use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
+use crate::modint::{add_mod, mul_mod, sub_mod};
+use crate::polynomial::Polynomial;
+use crate::random::Random;
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// 拡大次数の上限。元の個数 p^k が```u64```に収まるので k < 64。
+const MAX_DEGREE: usize = 64;
+
+thread_local! {
+    /// 現在の体。```GaloisField```が生きている間だけ設定される。
+    static FIELD: RefCell<Field> = const {
+        RefCell::new(Field {
+            characteristic: 0,
+            modulus: Vec::new(),
+            cardinality: 0,
+        })
+    };
+}
+
+/// 有限体 F_p[t]/(m(t)) の情報
+#[derive(Clone, Debug, Default)]
+struct Field {
+    /// 標数 p
+    characteristic: u64,
+    /// モニックな既約多項式 m(t) の係数。定数項から順に並べ、最高次の```1```は除く。
+    modulus: Vec<u64>,
+    /// 元の個数 p^k
+    cardinality: u64,
+}
+
+impl Field {
+    /// 拡大次数 k
+    fn degree(&self) -> usize {
+        self.modulus.len()
+    }
+
+    /// 元の番号を t の多項式としての係数（p 進表示の各桁）に分ける
+    fn digits(&self, index: u64) -> [u64; MAX_DEGREE] {
+        let mut digits = [0; MAX_DEGREE];
+        let mut n = index;
+        for d in digits.iter_mut().take(self.degree()) {
+            *d = n % self.characteristic;
+            n /= self.characteristic;
+        }
+        digits
+    }
+
+    /// ```digits```の逆
+    fn index(&self, digits: &[u64]) -> u64 {
+        digits[..self.degree()]
+            .iter()
+            .rev()
+            .fold(0, |n, &d| n * self.characteristic + d)
+    }
+}
+
+/// 現在の体を使って計算する。```GaloisField```が無い場合はパニックする。
+fn with_field<R>(f: impl FnOnce(&Field) -> R) -> R {
+    FIELD.with(|field| {
+        let field = field.borrow();
+        assert!(field.characteristic > 0, "GaloisField で体が設定されていません");
+        f(&field)
+    })
+}
+
+/// 有限体を作れない場合の誤り
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GaloisFieldError {
+    /// 法の多項式が既約でない（定数の場合も含む）
+    NotIrreducible,
+    /// 元の個数 p^k が```u64```に収まらない
+    TooLarge,
+}
+
+impl fmt::Display for GaloisFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GaloisFieldError::NotIrreducible => write!(f, "法の多項式が既約ではありません"),
+            GaloisFieldError::TooLarge => write!(f, "体の元の個数が大きすぎます"),
+        }
+    }
+}
+
+/// ```GfElem```の体 F_{p^k} = F_p[t]/(m(t)) を実行時に設定するためのコンテキスト。
+///
+/// ```DynModContext```と同様に、生成すると（そのスレッドで）体が設定され、破棄すると元の体に戻る。
+/// 標数 p は素数とする。
+pub struct GaloisField {
+    previous: Field,
+}
+
+impl GaloisField {
+    /// 既約多項式 m(t) の係数（定数項から順、mod p で簡約する）を与えて F_p[t]/(m(t)) を設定する。
+    /// m はモニックでなくてもよい。
+    pub fn new(p: u64, modulus: &[u64]) -> Result<Self, GaloisFieldError> {
+        let modulus = {
+            let _base = DynModContext::new(p);
+            let v: Vec<DynModInt> = modulus.iter().map(|&c| DynModInt::new(c)).collect();
+            let m = Polynomial::new(&v);
+            if !m.is_irreducible() {
+                return Err(GaloisFieldError::NotIrreducible);
+            }
+            let m = m.monic();
+            m.coefficients()[..m.deg()]
+                .iter()
+                .map(|c| c.to_int())
+                .collect::<Vec<u64>>()
+        };
+        let cardinality = p
+            .checked_pow(modulus.len() as u32)
+            .ok_or(GaloisFieldError::TooLarge)?;
+        let field = Field {
+            characteristic: p,
+            modulus,
+            cardinality,
+        };
+        let previous = FIELD.with(|f| f.replace(field));
+        Ok(GaloisField { previous })
+    }
+
+    /// 次数```k```の既約多項式を1つ選んで F_{p^k} を設定する。
+    ///
+    /// 既約多項式は```Random::default()```から決まるので、同じ p, k なら毎回同じものになる。
+    pub fn with_degree(p: u64, k: usize) -> Result<Self, GaloisFieldError> {
+        if k == 0 {
+            return Err(GaloisFieldError::NotIrreducible);
+        }
+        if k >= MAX_DEGREE || p.checked_pow(k as u32).is_none() {
+            return Err(GaloisFieldError::TooLarge);
+        }
+        let modulus: Vec<u64> = {
+            let _base = DynModContext::new(p);
+            let m = Polynomial::<DynModInt>::random_irreducible(k, &mut Random::default());
+            m.coefficients().iter().map(|c| c.to_int()).collect()
+        };
+        Self::new(p, &modulus)
+    }
+
+    /// 標数 p
+    pub fn characteristic(&self) -> u64 {
+        with_field(|field| field.characteristic)
+    }
+
+    /// 拡大次数 k
+    pub fn degree(&self) -> usize {
+        with_field(|field| field.degree())
+    }
+
+    /// 法のモニックな既約多項式 m(t) の係数。定数項から最高次の```1```まで並べる。
+    pub fn modulus(&self) -> Vec<u64> {
+        with_field(|field| {
+            let mut v = field.modulus.clone();
+            v.push(1);
+            v
+        })
+    }
+}
+
+impl Drop for GaloisField {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.previous);
+        FIELD.with(|f| f.replace(previous));
+    }
+}
+
+/// 有限体 F_{p^k} = F_p[t]/(m(t)) の元。体は```GaloisField```から読み出す。
+///
+/// t の多項式としての係数 a_0 + a_1 t + ... + a_{k-1} t^{k-1} を、番号 Σ a_i p^i として格納する。
+/// ```DynModInt```と同様に```Polynomial```の係数や解法に使える。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GfElem {
+    index: u64,
+}
+
+impl GfElem {
+    /// t の多項式としての係数（定数項から順、mod p で簡約する）から元を作る。
+    /// 次数が k 以上なら m(t) で割った余りをとる。
+    pub fn new(coefficients: &[u64]) -> Self {
+        let (p, t) = with_field(|field| (field.characteristic, GfElem::t()));
+        coefficients
+            .iter()
+            .rev()
+            .fold(GfElem::zero(), |a, &c| a * t + GfElem::element(c % p))
+    }
+
+    /// 生成元 t（k = 1 の場合は -m(0)）
+    pub fn t() -> Self {
+        with_field(|field| {
+            if field.degree() == 1 {
+                GfElem {
+                    index: (field.characteristic - field.modulus[0]) % field.characteristic,
+                }
+            } else {
+                GfElem {
+                    index: field.characteristic,
+                }
+            }
+        })
+    }
+
+    /// t の多項式としての係数。定数項から順に k 個並べる。
+    pub fn coefficients(&self) -> Vec<u64> {
+        with_field(|field| field.digits(self.index)[..field.degree()].to_vec())
+    }
+
+    /// 繰り返し二乗法によるべき乗の計算
+    pub fn pow(&self, n: u64) -> Self {
+        let mut res = GfElem::identity();
+        let mut a = *self;
+        let mut m = n;
+        while m > 0 {
+            if m % 2 == 1 {
+                res *= a;
+            }
+            a *= a;
+            m /= 2;
+        }
+        res
+    }
+
+    /// フロベニウス写像 a ↦ a^p
+    pub fn frobenius(&self) -> Self {
+        self.pow(Self::characteristic())
+    }
+
+    /// 共役 a, a^p, ..., a^(p^(k-1))
+    fn conjugates(&self) -> Vec<Self> {
+        let k = with_field(|field| field.degree());
+        let mut v = vec![*self];
+        for i in 1..k {
+            v.push(v[i - 1].frobenius());
+        }
+        v
+    }
+
+    /// F_p へのトレース a + a^p + ... + a^(p^(k-1))。値は F_p（番号が p 未満の元）に属する。
+    pub fn trace(&self) -> Self {
+        self.conjugates()
+            .into_iter()
+            .fold(GfElem::zero(), |s, a| s + a)
+    }
+
+    /// F_p へのノルム a・a^p・...・a^(p^(k-1))。値は F_p（番号が p 未満の元）に属する。
+    pub fn norm(&self) -> Self {
+        self.conjugates()
+            .into_iter()
+            .fold(GfElem::identity(), |s, a| s * a)
+    }
+}
+
+/// t の多項式として、```Polynomial```と同じく次数の低い順に表示する。
+impl fmt::Display for GfElem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.coefficients();
+        let mut terms: Vec<String> = Vec::new();
+        for (i, &d) in digits.iter().enumerate() {
+            if d == 0 {
+                continue;
+            }
+            let mut s = String::new();
+            if i == 0 || d != 1 {
+                s.push_str(&d.to_string());
+            }
+            if i >= 1 {
+                s.push('t');
+            }
+            if i > 1 {
+                s.push('^');
+                s.push_str(&i.to_string());
+            }
+            terms.push(s);
+        }
+        if terms.is_empty() {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", terms.join(" + "))
+        }
+    }
+}
+
+impl Add for GfElem {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        with_field(|field| {
+            let p = field.characteristic;
+            let mut a = field.digits(self.index);
+            let b = field.digits(rhs.index);
+            for (x, &y) in a.iter_mut().zip(b.iter()) {
+                *x = add_mod(*x, y, p);
+            }
+            GfElem {
+                index: field.index(&a),
+            }
+        })
+    }
+}
+
+impl AddAssign for GfElem {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for GfElem {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        with_field(|field| {
+            let p = field.characteristic;
+            let mut a = field.digits(self.index);
+            let b = field.digits(rhs.index);
+            for (x, &y) in a.iter_mut().zip(b.iter()) {
+                *x = sub_mod(*x, y, p);
+            }
+            GfElem {
+                index: field.index(&a),
+            }
+        })
+    }
+}
+
+impl SubAssign for GfElem {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+/// t の多項式として掛けてから、t^k = -(m_0 + m_1 t + ... + m_{k-1} t^{k-1}) で次数を下げる。
+impl Mul for GfElem {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        with_field(|field| {
+            let p = field.characteristic;
+            let k = field.degree();
+            let a = field.digits(self.index);
+            let b = field.digits(rhs.index);
+            let mut c = [0; 2 * MAX_DEGREE];
+            for i in 0..k {
+                if a[i] == 0 {
+                    continue;
+                }
+                for j in 0..k {
+                    c[i + j] = add_mod(c[i + j], mul_mod(a[i], b[j], p), p);
+                }
+            }
+            for i in (k..(2 * k - 1)).rev() {
+                let top = c[i];
+                if top == 0 {
+                    continue;
+                }
+                c[i] = 0;
+                for (j, &m) in field.modulus.iter().enumerate() {
+                    c[i - k + j] = sub_mod(c[i - k + j], mul_mod(top, m, p), p);
+                }
+            }
+            GfElem {
+                index: field.index(&c),
+            }
+        })
+    }
+}
+
+impl MulAssign for GfElem {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+/// unary negation の実装
+impl Neg for GfElem {
+    type Output = Self;
+    fn neg(self) -> Self {
+        GfElem::zero() - self
+    }
+}
+
+impl Zero for GfElem {
+    fn zero() -> Self {
+        GfElem { index: 0 }
+    }
+}
+
+impl Identity for GfElem {
+    fn identity() -> Self {
+        GfElem { index: 1 }
+    }
+}
+
+/// 零でない元 a について a^(q-2) を返す。
+impl Inverse for GfElem {
+    fn inverse(self) -> Option<GfElem> {
+        if self == GfElem::zero() {
+            None
+        } else {
+            Some(self.pow(Self::cardinality() - 2))
+        }
+    }
+}
+
+impl FiniteRing for GfElem {
+    fn cardinality() -> u64 {
+        with_field(|field| field.cardinality)
+    }
+
+    fn element(i: u64) -> Self {
+        GfElem {
+            index: i % Self::cardinality(),
+        }
+    }
+
+    fn index(&self) -> u64 {
+        self.index
+    }
+}
+
+impl FiniteField for GfElem {
+    fn characteristic() -> u64 {
+        with_field(|field| field.characteristic)
+    }
+
+    /// フロベニウス写像の逆 a ↦ a^(p^(k-1))
+    fn pth_root(self) -> Self {
+        self.pow(Self::cardinality() / Self::characteristic())
+    }
+}
+
+impl Convolution for GfElem {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// F_{5^3} = F_5[t]/(t^3 + t + 1) のすべての元
+    fn elements() -> Vec<GfElem> {
+        (0..GfElem::cardinality()).map(GfElem::element).collect()
+    }
+
+    #[test]
+    fn field_axioms() {
+        let _field = GaloisField::new(5, &[1, 1, 0, 1]).unwrap();
+        let all = elements();
+        let (zero, one) = (GfElem::zero(), GfElem::identity());
+        for &a in &all {
+            assert_eq!(a + zero, a);
+            assert_eq!(a * one, a);
+            assert_eq!(a * zero, zero);
+            assert_eq!(a + (-a), zero);
+            assert_eq!(a - a, zero);
+            for &b in &all {
+                assert_eq!(a + b, b + a);
+                assert_eq!(a * b, b * a);
+                assert_eq!(a - b + b, a);
+                // 零因子を持たない
+                assert_eq!(a * b == zero, a == zero || b == zero, "{} * {}", a, b);
+            }
+        }
+        let mut random = Random::default();
+        for _ in 0..2000 {
+            let a = all[random.below(125) as usize];
+            let b = all[random.below(125) as usize];
+            let c = all[random.below(125) as usize];
+            assert_eq!((a + b) + c, a + (b + c));
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * (b + c), a * b + a * c);
+        }
+    }
+
+    #[test]
+    fn inverse_and_order() {
+        let _field = GaloisField::new(5, &[1, 1, 0, 1]).unwrap();
+        assert_eq!(GfElem::zero().inverse(), None);
+        for &a in &elements()[1..] {
+            assert_eq!(a * a.inverse().unwrap(), GfElem::identity(), "{}", a);
+            assert_eq!(a.pow(124), GfElem::identity());
+        }
+        // t^3 = -t - 1
+        let t = GfElem::t();
+        assert_eq!(t.pow(3), GfElem::new(&[4, 4]));
+        assert_eq!(GfElem::new(&[1, 1, 0, 1]), GfElem::zero());
+        assert_eq!(GfElem::new(&[7, 11, 3]).coefficients(), vec![2, 1, 3]);
+        assert_eq!(GfElem::new(&[2, 1, 3]).to_string(), "2 + t + 3t^2");
+    }
+
+    #[test]
+    fn frobenius_and_pth_root() {
+        let _field = GaloisField::new(5, &[1, 1, 0, 1]).unwrap();
+        let all = elements();
+        for &a in &all {
+            assert_eq!(a.frobenius(), a.pow(5));
+            assert_eq!(a.frobenius().frobenius().frobenius(), a);
+            assert_eq!(a.pth_root().frobenius(), a);
+            assert_eq!(a.frobenius().pth_root(), a);
+            for &b in all.iter().step_by(7) {
+                assert_eq!((a + b).frobenius(), a.frobenius() + b.frobenius());
+                assert_eq!((a * b).frobenius(), a.frobenius() * b.frobenius());
+            }
+        }
+        // F_5 の元はフロベニウス写像で動かない
+        for i in 0..5 {
+            let a = GfElem::element(i);
+            assert_eq!(a.frobenius(), a);
+        }
+        assert_eq!(GfElem::characteristic(), 5);
+    }
+
+    #[test]
+    fn trace_and_norm_lie_in_prime_field() {
+        let _field = GaloisField::new(5, &[1, 1, 0, 1]).unwrap();
+        let all = elements();
+        for &a in &all {
+            assert!(a.trace().index() < 5, "Tr({}) = {}", a, a.trace());
+            assert!(a.norm().index() < 5, "N({}) = {}", a, a.norm());
+            assert_eq!(a.norm() == GfElem::zero(), a == GfElem::zero());
+            for &b in all.iter().step_by(11) {
+                assert_eq!((a + b).trace(), a.trace() + b.trace());
+                assert_eq!((a * b).norm(), a.norm() * b.norm());
+            }
+        }
+        // トレースは全射なので、各値を 25 個ずつとる
+        let mut counts = [0; 5];
+        for &a in &all {
+            counts[a.trace().index() as usize] += 1;
+        }
+        assert_eq!(counts, [25; 5]);
+        // t の最小多項式 t^3 + t + 1 から Tr(t) = 0、N(t) = -1
+        assert_eq!(GfElem::t().trace(), GfElem::zero());
+        assert_eq!(GfElem::t().norm(), GfElem::element(4));
+    }
+
+    #[test]
+    fn context_errors_and_nesting() {
+        // t^2 + 1 = (t - 2)(t + 2) は F_5 で可約
+        assert!(matches!(
+            GaloisField::new(5, &[1, 0, 1]),
+            Err(GaloisFieldError::NotIrreducible)
+        ));
+        assert!(matches!(
+            GaloisField::new(5, &[3]),
+            Err(GaloisFieldError::NotIrreducible)
+        ));
+        assert!(matches!(
+            GaloisField::with_degree(2, 64),
+            Err(GaloisFieldError::TooLarge)
+        ));
+        assert!(matches!(
+            GaloisField::with_degree(1_000_003, 4),
+            Err(GaloisFieldError::TooLarge)
+        ));
+        let outer = GaloisField::new(5, &[1, 1, 0, 1]).unwrap();
+        {
+            let inner = GaloisField::with_degree(2, 4).unwrap();
+            assert_eq!((inner.characteristic(), inner.degree()), (2, 4));
+            assert_eq!(GfElem::cardinality(), 16);
+        }
+        // 内側の体を破棄すると元の体に戻る
+        assert_eq!(GfElem::cardinality(), 125);
+        assert_eq!(outer.modulus(), vec![1, 1, 0, 1]);
+    }
+}
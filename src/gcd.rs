@@ -0,0 +1,299 @@
+use crate::convolution::Convolution;
+use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
+use crate::polynomial::Polynomial;
+
+/// ```gcd```は次数がこれ以上なら half-GCD を使う（これより小さいと互除法の方が速い）
+const HALF_GCD_MIN_DEGREE: usize = 16384;
+
+/// half-GCD の中で、次数がこれより小さい部分は互除法で直接求める
+const HALF_GCD_THRESHOLD: usize = 256;
+
+/// 多項式を成分とする 2 × 2 行列。互除法の何ステップか分の変換を表す。
+type Matrix<T> = [[Polynomial<T>; 2]; 2];
+
+/// 係数環は体とする（最高次係数が可逆でないとパニックする）。
+impl<T: Convolution + Identity + Inverse + Eq> Polynomial<T> {
+    /// 最高次係数で割ってモニックにする。零多項式はそのまま。
+    pub fn monic(&self) -> Self {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let c = self
+            .leading_coefficient()
+            .inverse()
+            .expect("最高次係数が可逆ではありません");
+        self.clone() * Polynomial::new_constant(c)
+    }
+
+    /// 最大公約式（モニック）。両方とも零多項式なら零多項式を返す。
+    ///
+    /// 次数が大きい場合は half-GCD を使う。
+    pub fn gcd(f: &Self, g: &Self) -> Self {
+        if f.deg().max(g.deg()) >= HALF_GCD_MIN_DEGREE {
+            Self::gcd_half(f, g)
+        } else {
+            Self::gcd_euclid(f, g)
+        }
+    }
+
+    /// ユークリッドの互除法による最大公約式（モニック）。計算量は O(n^2)。
+    pub fn gcd_euclid(f: &Self, g: &Self) -> Self {
+        let mut a = f.clone();
+        let mut b = g.clone();
+        while !b.is_zero() {
+            let r = remainder(&a, &b);
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    /// half-GCD による最大公約式（モニック）。計算量は O(M(n) log n)
+    /// （M(n) は次数 n の多項式の掛け算の計算量）。
+    pub fn gcd_half(f: &Self, g: &Self) -> Self {
+        let (a, _) = reduce_to_gcd(f, g);
+        a.monic()
+    }
+
+    /// 拡張ユークリッドの互除法。```s f + t g = d```となる```(d, s, t)```を返す。
+    /// ```d```はモニックな最大公約式。両方とも零多項式なら```(0, 1, 0)```。
+    ///
+    /// 次数が大きい場合は half-GCD で変換行列を求める。
+    pub fn extended_gcd(f: &Self, g: &Self) -> (Self, Self, Self) {
+        let (a, m) = reduce_to_gcd(f, g);
+        if a.is_zero() {
+            return (a, Self::identity(), Self::zero());
+        }
+        let c = Polynomial::new_constant(
+            a.leading_coefficient()
+                .inverse()
+                .expect("最高次係数が可逆ではありません"),
+        );
+        let [[s, t], _] = m;
+        (a * c.clone(), s * c.clone(), t * c)
+    }
+
+    /// 最小公倍式（モニック）。どちらかが零多項式なら零多項式を返す。
+    pub fn lcm(f: &Self, g: &Self) -> Self {
+        if f.is_zero() || g.is_zero() {
+            return Self::zero();
+        }
+        let d = Self::gcd(f, g);
+        (f.exact_div(&d).unwrap() * g.clone()).monic()
+    }
+}
+
+/// ```b```で割った余り
+fn remainder<T>(a: &Polynomial<T>, b: &Polynomial<T>) -> Polynomial<T>
+where
+    T: Convolution + Identity + Inverse + Eq,
+{
+    match a.div_rem(b) {
+        Ok((_, r)) => r,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// 次数。零多項式は -1 とする。
+fn degree<T: Zero + Eq>(f: &Polynomial<T>) -> isize {
+    f.strict_deg().map_or(-1, |d| d as isize)
+}
+
+/// ```x^k```で割った商（下から```k```個の係数を捨てる）
+fn shift_down<T: Zero + Eq + Copy>(f: &Polynomial<T>, k: usize) -> Polynomial<T> {
+    Polynomial::new(&f.coefficients()[k.min(f.coefficients().len())..])
+}
+
+fn identity_matrix<T: Convolution + Identity + Eq>() -> Matrix<T> {
+    [
+        [Polynomial::identity(), Polynomial::zero()],
+        [Polynomial::zero(), Polynomial::identity()],
+    ]
+}
+
+/// 行列の積```m * n```
+fn multiply<T: Convolution + Eq>(m: &Matrix<T>, n: &Matrix<T>) -> Matrix<T> {
+    let entry = |i: usize, j: usize| {
+        m[i][0].clone() * n[0][j].clone() + m[i][1].clone() * n[1][j].clone()
+    };
+    [[entry(0, 0), entry(0, 1)], [entry(1, 0), entry(1, 1)]]
+}
+
+/// 行列をベクトル```(a, b)```に掛ける
+fn apply<T: Convolution + Eq>(
+    m: &Matrix<T>,
+    a: &Polynomial<T>,
+    b: &Polynomial<T>,
+) -> (Polynomial<T>, Polynomial<T>) {
+    (
+        m[0][0].clone() * a.clone() + m[0][1].clone() * b.clone(),
+        m[1][0].clone() * a.clone() + m[1][1].clone() * b.clone(),
+    )
+}
+
+/// 互除法の1ステップ```(a, b) -> (b, a - q b)```を行い、それまでの変換```m```に
+/// このステップの変換```[[0, 1], [1, -q]]```を左から掛けたものを返す
+fn euclid_step<T>(a: &mut Polynomial<T>, b: &mut Polynomial<T>, m: Matrix<T>) -> Matrix<T>
+where
+    T: Convolution + Identity + Inverse + Eq,
+{
+    let (q, r) = match a.div_rem(b) {
+        Ok(qr) => qr,
+        Err(e) => panic!("{}", e),
+    };
+    *a = std::mem::replace(b, r);
+    let [[m00, m01], [m10, m11]] = m;
+    let n10 = m00 - q.clone() * m10.clone();
+    let n11 = m01 - q * m11.clone();
+    [[m10, m11], [n10, n11]]
+}
+
+/// half-GCD。```deg a > deg b```とし、n = deg a、m = ceil(n / 2) とする。
+///
+/// 互除法の何ステップか分の変換```M```で、```M (a, b) = (c, d)```が
+/// ```deg d < m```を満たすものを返す（可能な限り少ないステップで止める）。
+fn half_gcd<T>(a: &Polynomial<T>, b: &Polynomial<T>) -> Matrix<T>
+where
+    T: Convolution + Identity + Inverse + Eq,
+{
+    let n = degree(a);
+    let m = (n + 1) / 2;
+    if degree(b) < m {
+        return identity_matrix();
+    }
+
+    // 次数が小さければ互除法で直接求める
+    if (n as usize) < HALF_GCD_THRESHOLD {
+        let mut r = identity_matrix();
+        let (mut a, mut b) = (a.clone(), b.clone());
+        while degree(&b) >= m {
+            r = euclid_step(&mut a, &mut b, r);
+        }
+        return r;
+    }
+
+    // 上位の係数だけで前半のステップを決める
+    let r = half_gcd(&shift_down(a, m as usize), &shift_down(b, m as usize));
+    let (mut a, mut b) = apply(&r, a, b);
+    if degree(&b) < m {
+        return r;
+    }
+    let r = euclid_step(&mut a, &mut b, r);
+    if degree(&b) < m {
+        return r;
+    }
+    // 残りのステップも上位の係数だけで決める
+    let k = (2 * m - degree(&a)) as usize;
+    let s = half_gcd(&shift_down(&a, k), &shift_down(&b, k));
+    multiply(&s, &r)
+}
+
+/// half-GCD を繰り返して```(f, g)```を```(gcd, 0)```まで変換し、
+/// 最大公約式（モニックとは限らない）と変換行列を返す。
+fn reduce_to_gcd<T>(f: &Polynomial<T>, g: &Polynomial<T>) -> (Polynomial<T>, Matrix<T>)
+where
+    T: Convolution + Identity + Inverse + Eq,
+{
+    let (mut a, mut b) = (f.clone(), g.clone());
+    let mut m = identity_matrix();
+    if degree(&a) < degree(&b) {
+        std::mem::swap(&mut a, &mut b);
+        m = [
+            [Polynomial::zero(), Polynomial::identity()],
+            [Polynomial::identity(), Polynomial::zero()],
+        ];
+    }
+    while !b.is_zero() {
+        if degree(&a) == degree(&b) {
+            m = euclid_step(&mut a, &mut b, m);
+            continue;
+        }
+        let h = half_gcd(&a, &b);
+        let (c, d) = apply(&h, &a, &b);
+        a = c;
+        b = d;
+        m = multiply(&h, &m);
+        if !b.is_zero() {
+            m = euclid_step(&mut a, &mut b, m);
+        }
+    }
+    (a, m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modint::ModInt;
+    use crate::random::Random;
+    use crate::testing::random_polynomial;
+
+    type F = ModInt<998_244_353>;
+
+    /// ```s f + t g = d```で、```d```が```f```と```g```を割り切ることを確かめる
+    fn check_bezout(f: &Polynomial<F>, g: &Polynomial<F>) -> Polynomial<F> {
+        let (d, s, t) = Polynomial::extended_gcd(f, g);
+        assert_eq!(s.clone() * f.clone() + t * g.clone(), d);
+        assert!(f.is_divisible_by(&d) && g.is_divisible_by(&d));
+        d
+    }
+
+    #[test]
+    fn half_gcd_matches_euclid() {
+        let mut random = Random::default();
+        for &(n, k) in &[
+            (0, 0),
+            (1, 0),
+            (5, 3),
+            (300, 0),
+            (300, 100),
+            (1000, 400),
+            (2000, 1999),
+        ] {
+            let d = random_polynomial(k, &mut random);
+            let f = random_polynomial(n, &mut random) * d.clone();
+            let g = random_polynomial(n / 2 + 1, &mut random) * d.clone();
+            let expected = Polynomial::gcd_euclid(&f, &g);
+            assert_eq!(
+                Polynomial::gcd_half(&f, &g),
+                expected,
+                "n = {}, k = {}",
+                n,
+                k
+            );
+            assert_eq!(
+                Polynomial::gcd_half(&g, &f),
+                expected,
+                "n = {}, k = {}",
+                n,
+                k
+            );
+            assert_eq!(check_bezout(&f, &g), expected, "n = {}, k = {}", n, k);
+        }
+    }
+
+    #[test]
+    fn gcd_above_half_gcd_min_degree() {
+        let mut random = Random::default();
+        let n = HALF_GCD_MIN_DEGREE + 100;
+        let d = random_polynomial(1000, &mut random);
+        let f = random_polynomial(n, &mut random) * d.clone();
+        let g = random_polynomial(n - 3000, &mut random) * d.clone();
+        // ランダムな f / d, g / d は高い確率で互いに素
+        assert_eq!(Polynomial::gcd(&f, &g), d.monic());
+        assert_eq!(check_bezout(&f, &g), d.monic());
+    }
+
+    #[test]
+    fn gcd_with_zero() {
+        let mut random = Random::default();
+        let f = random_polynomial(10, &mut random);
+        let zero = Polynomial::zero();
+        assert_eq!(Polynomial::gcd(&f, &zero), f.monic());
+        assert_eq!(Polynomial::gcd_half(&zero, &f), f.monic());
+        assert_eq!(check_bezout(&zero, &f), f.monic());
+        let (d, s, t) = Polynomial::extended_gcd(&zero, &zero);
+        assert!(d.is_zero() && s == Polynomial::identity() && t.is_zero());
+        assert!(Polynomial::lcm(&f, &zero).is_zero());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::convolution::Convolution;
+use crate::finite_ring::FiniteField;
+use crate::identities::Identity;
+use crate::polynomial::Polynomial;
+use crate::singular::singular_points;
+use crate::zeta::{genus, points_at_infinity_smooth};
+use std::ops::MulAssign;
+
+/// アフィンの解の個数 N が Hasse–Weil の評価を満たすかどうかの報告
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HasseWeilReport {
+    /// 体の元の個数 q
+    pub q: u64,
+    /// 射影非特異モデルの種数 g（の上界）
+    pub genus: usize,
+    /// アフィンの解の個数 N
+    pub affine_points: u64,
+    /// 射影非特異モデルの無限遠の点で F_q に属するものの個数
+    pub points_at_infinity: u64,
+    /// アフィン特異点の個数
+    pub singular_points: usize,
+    /// 補正項 |(無限遠の点の個数) - 1| + Σ_P max(1, m_P - 1)（m_P は特異点 P の重複度）
+    pub correction: u64,
+}
+
+impl HasseWeilReport {
+    /// N - q
+    pub fn deviation(&self) -> i128 {
+        self.affine_points as i128 - self.q as i128
+    }
+
+    /// 評価の右辺 2g√q + 補正
+    pub fn bound(&self) -> f64 {
+        2.0 * self.genus as f64 * (self.q as f64).sqrt() + self.correction as f64
+    }
+
+    /// |N - q| <= 2g√q + 補正 が成り立つか。平方根を使わず整数で判定する。
+    pub fn holds(&self) -> bool {
+        let excess = self.deviation().abs() - self.correction as i128;
+        excess <= 0 || excess * excess <= 4 * (self.genus as i128).pow(2) * self.q as i128
+    }
+}
+
+/// 曲線 f(x) = g(y) のアフィンの解の個数```affine_points```について Hasse–Weil の評価を確かめる。
+///
+/// 射影非特異モデルの点の個数を N' とすると |N' - (q + 1)| <= 2g√q が成り立つ。
+/// N' は N に無限遠の点を加え、特異点 P をその上にある点（高々 m_P 個）に置き換えたものなので、
+/// |N - q| <= 2g√q + |(無限遠の点の個数) - 1| + Σ_P max(1, m_P - 1) となる。
+/// 種数は```zeta::genus```で求め、特異点があっても上界として使える。種数が求められなければ```None```。
+pub fn hasse_weil_check<T>(
+    f: &Polynomial<T>,
+    g: &Polynomial<T>,
+    affine_points: u64,
+) -> Option<HasseWeilReport>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    let genus = genus(f.deg(), g.deg(), T::characteristic())?;
+    let at_infinity = points_at_infinity_smooth(f, g);
+    let singular = singular_points(f, g);
+    let correction = (at_infinity as i128 - 1).unsigned_abs() as u64
+        + singular
+            .iter()
+            .map(|p| p.multiplicity.saturating_sub(1).max(1) as u64)
+            .sum::<u64>();
+    Some(HasseWeilReport {
+        q: T::cardinality(),
+        genus,
+        affine_points,
+        points_at_infinity: at_infinity,
+        singular_points: singular.len(),
+        correction,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::random::Random;
+    use crate::solver::count_solutions;
+    use crate::testing::{polynomial, random_polynomial};
+
+    fn report(q: u64, genus: usize, affine_points: u64, correction: u64) -> HasseWeilReport {
+        HasseWeilReport {
+            q,
+            genus,
+            affine_points,
+            points_at_infinity: 1,
+            singular_points: 0,
+            correction,
+        }
+    }
+
+    #[test]
+    fn elliptic_curve_over_f5() {
+        // y^2 = x^3 + x + 1 は 8 個のアフィンの点をもち、|8 - 5| <= 2√5
+        let _context = DynModContext::new(5);
+        let (f, g) = (
+            polynomial::<DynModInt>(&[1, 1, 0, 1]),
+            polynomial(&[0, 0, 1]),
+        );
+        let r = hasse_weil_check(&f, &g, count_solutions(&f, &g)).unwrap();
+        assert_eq!(r, report(5, 1, 8, 0));
+        assert_eq!(r.deviation(), 3);
+        assert!((r.bound() - 2.0 * 5f64.sqrt()).abs() < 1e-9);
+        assert!(r.holds());
+    }
+
+    #[test]
+    fn holds_compares_exactly() {
+        // |N - q| = 2g√q ちょうどは成り立つ
+        assert!(report(4, 1, 8, 0).holds());
+        assert!(report(4, 1, 0, 0).holds());
+        assert!(!report(4, 1, 9, 0).holds());
+        assert!(!report(5, 1, 10, 0).holds());
+        assert!(report(5, 1, 10, 1).holds());
+        // 種数 0 なら補正の範囲でしかずれない
+        assert!(report(7, 0, 9, 2).holds());
+        assert!(!report(7, 0, 10, 2).holds());
+    }
+
+    #[test]
+    fn bound_holds_for_random_curves() {
+        let mut random = Random::default();
+        for &p in &[3, 5, 7, 11, 13] {
+            let _context = DynModContext::new(p);
+            for _ in 0..30 {
+                let f = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let g = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let n = count_solutions(&f, &g);
+                match hasse_weil_check(&f, &g, n) {
+                    Some(r) => {
+                        assert!(r.holds(), "{} = {} mod {}: {:?}", f, g.print_f_of_y(), p, r)
+                    }
+                    None => assert!(genus(f.deg(), g.deg(), p).is_none()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn singular_points_enter_the_correction() {
+        // y^2 = x^3 は原点に重複度 2 の尖点をもつ
+        let _context = DynModContext::new(7);
+        let (f, g) = (
+            polynomial::<DynModInt>(&[0, 0, 0, 1]),
+            polynomial(&[0, 0, 1]),
+        );
+        let r = hasse_weil_check(&f, &g, count_solutions(&f, &g)).unwrap();
+        assert_eq!((r.singular_points, r.correction), (1, 1));
+        assert_eq!(r.affine_points, 7);
+        assert!(r.holds());
+        assert_eq!(hasse_weil_check(&polynomial(&[1]), &g, 0), None);
+    }
+}
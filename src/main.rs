@@ -1,135 +1,831 @@
-const P: u64 = 5; // ここを適宜書き換える
-
+pub mod bench;
+pub mod bivariate;
+pub mod cli;
+pub mod convolution;
+pub mod dyn_modint;
+pub mod factor;
+pub mod finite_ring;
+pub mod galois_field;
+pub mod gcd;
+pub mod hasse_weil;
 pub mod identities;
 pub mod inverse;
 pub mod modint;
+pub mod montgomery;
+pub mod multivariate;
+pub mod ntt;
+pub mod parser;
 pub mod polynomial;
+pub mod projective;
+pub mod random;
+pub mod roots;
+pub mod resultant;
+pub mod singular;
 pub mod solution_set;
+pub mod solver;
+pub mod system;
+#[cfg(test)]
+pub mod testing;
+pub mod zeta;
 
-use crate::identities::Zero;
-use crate::modint::ModInt;
+use crate::bivariate::BivariatePolynomial;
+use crate::cli::{CliError, Config, Method};
+use crate::convolution::Convolution;
+use crate::dyn_modint::{DynModContext, DynModInt};
+use crate::finite_ring::{FiniteField, FiniteRing};
+use crate::galois_field::{GaloisField, GfElem};
+use crate::identities::{Identity, Zero};
+use crate::modint::mul_mod;
+use crate::multivariate::{
+    count_multivariate_solutions, multivariate_solutions, solve_multivariate_brute_force, Point,
+};
 use crate::polynomial::Polynomial;
+use crate::projective::{ProjectiveClosure, ProjectivePoint};
 use crate::solution_set::SolutionSet;
+use crate::solver::{
+    bivariate_solutions, count_bivariate_solutions, count_solutions, solutions,
+    solutions_by_roots, solve_bivariate_brute_force, solve_equation_brute_force,
+};
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::ops::{MulAssign, Neg};
+
+/// 方法を指定しない場合に値の表で解く p の上限
+const TABLE_LIMIT: u64 = 1 << 26;
 
-use std::collections::HashSet;
+/// 根を求める方法で解の個数を数える p の上限。すべての x について根を求めるので O(p polylog p) かかる。
+const ROOTS_COUNT_LIMIT: u64 = 1 << 26;
 
 fn main() {
-    println!("mod {} での f(x) = g(y) の形の方程式の解を求めます。", P);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("エラー: {}", e);
+        eprintln!("使い方は --help で表示できます。");
+        std::process::exit(1);
+    }
+}
 
-    if !is_prime(P) {
-        println!("注：{}は素数ではありません。", P);
+/// 引数を解釈して方程式を解く。足りない値は標準入力から読む。
+fn run(args: &[String]) -> Result<(), CliError> {
+    let config = cli::parse_args(args)?;
+    if config.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    if config.bench {
+        bench::run();
+        return Ok(());
     }
 
-    println!("fの次数を入力");
+    let stdin = std::io::stdin();
+    let mut input = stdin.lock();
 
-    // 解く方程式の左辺の次数
-    let mut n = String::new();
-    std::io::stdin().read_line(&mut n).ok();
-    // usize型に変換
-    let n: usize = n.trim().parse().ok().unwrap();
+    let p: u64 = match config.prime {
+        Some(p) => p,
+        None => {
+            println!("標数pを入力");
+            cli::parse_prime(&cli::read_line(&mut input)?)?
+        }
+    };
+    let _context = DynModContext::new(p);
 
-    let mut v: Vec<ModInt<P>> = vec![ModInt::<P>::zero(); n + 1];
+    // --stream のときは出力を解だけにするため、案内は表示しない
+    if !config.stream {
+        let variables = match config.sums.len() {
+            0 => "x, y".to_string(),
+            n if n <= 3 => {
+                let v: Vec<String> = (1..=n).map(|i| format!("x_{}", i)).collect();
+                v.join(", ")
+            }
+            n => format!("x_1, ..., x_{}", n),
+        };
+        println!("mod {} での {} の方程式の解を求めます。", p, variables);
+    }
 
-    for i in 0..=n {
-        println!("{}次の係数を入力", i);
-        let mut a = String::new();
-        std::io::stdin().read_line(&mut a).ok();
-        // u64型に変換
-        let a: u64 = a.trim().parse().ok().unwrap();
-        v[i] = ModInt::<P>::new(a);
+    if !is_prime(p) {
+        eprintln!("注：{}は素数ではありません。", p);
     }
 
-    let f: Polynomial<ModInt<P>> = Polynomial::new(&v);
+    if config.extension.is_none() && config.modulus.is_none() {
+        return solve::<DynModInt, _>(&config, p, &mut input);
+    }
+
+    // 拡大体 F_{p^k} 上で解く
+    let option = if config.modulus.is_some() {
+        "--modulus"
+    } else {
+        "--extension"
+    };
+    if !is_prime(p) {
+        return Err(CliError::RequiresPrime(option.to_string()));
+    }
+    let field = match &config.modulus {
+        Some(m) => {
+            let m: Vec<u64> = m.iter().map(|&c| cli::reduce(c, p)).collect();
+            GaloisField::new(p, &m)
+        }
+        None => GaloisField::with_degree(p, config.extension.unwrap()),
+    }
+    .map_err(|e| CliError::InvalidField(option.to_string(), e))?;
+    if !config.stream {
+        let m: Vec<DynModInt> = field.modulus().iter().map(|&c| DynModInt::new(c)).collect();
+        println!(
+            "係数体は F_{} = F_{}[t]/({}) とします。",
+            GfElem::cardinality(),
+            p,
+            Polynomial::new(&m).print_with_variable("t")
+        );
+    }
+    solve::<GfElem, _>(&config, p, &mut input)
+}
+
+/// 係数体```T```（F_p または F_{p^k}）の上で方程式を解いて表示する
+fn solve<T, R>(config: &Config, p: u64, input: &mut R) -> Result<(), CliError>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Neg<Output = T> + Ord + fmt::Display,
+    R: BufRead,
+{
+    if !config.sums.is_empty() {
+        return solve_multivariate::<T>(config, p);
+    }
 
-    println!("gの次数を入力");
+    // 方程式が2つ以上なら連立方程式として解く
+    if config.equations.len() >= 2 {
+        let mut equations: Vec<BivariatePolynomial<T>> = Vec::new();
+        for e in &config.equations {
+            equations.push(
+                parser::parse_bivariate(e)
+                    .map_err(|err| CliError::InvalidEquation(e.clone(), err))?,
+            );
+        }
+        return solve_system(config, p, &equations);
+    }
 
-    // 解く方程式の左辺の次数
-    let mut n = String::new();
-    std::io::stdin().read_line(&mut n).ok();
-    // usize型に変換
-    let n: usize = n.trim().parse().ok().unwrap();
+    // x と y の積の項を含む方程式は F(x, y) = 0 として解く
+    if let Some(e) = config.equations.first() {
+        let h: BivariatePolynomial<T> =
+            parser::parse_bivariate(e).map_err(|err| CliError::InvalidEquation(e.clone(), err))?;
+        if !h.is_separated() {
+            return solve_bivariate(config, p, &h);
+        }
+    }
 
-    let mut w: Vec<ModInt<P>> = vec![ModInt::<P>::zero(); n + 1];
+    let (f, g): (Polynomial<T>, Polynomial<T>) = match config.equations.first() {
+        Some(e) => {
+            parser::parse_equation(e).map_err(|err| CliError::InvalidEquation(e.clone(), err))?
+        }
+        None => {
+            let v = match &config.f {
+                Some(v) => v.clone(),
+                None => read_coefficients(input, "f")?,
+            };
+            let w = match &config.g {
+                Some(w) => w.clone(),
+                None => read_coefficients(input, "g")?,
+            };
+            (to_polynomial(&v, p), to_polynomial(&w, p))
+        }
+    };
 
-    for i in 0..=n {
-        println!("{}次の係数を入力", i);
-        let mut a = String::new();
-        std::io::stdin().read_line(&mut a).ok();
-        // u64型に変換
-        let a: u64 = a.trim().parse().ok().unwrap();
-        w[i] = ModInt::<P>::new(a);
+    if let Some(k_max) = config.zeta {
+        if !is_prime(p) {
+            return Err(CliError::RequiresPrime("--zeta".to_string()));
+        }
+        let q = u32::try_from(k_max).ok().and_then(|k| p.checked_pow(k));
+        if q.is_none_or(|q| q > TABLE_LIMIT) {
+            return Err(CliError::TooLarge("--zeta".to_string()));
+        }
+        print_zeta(p, &f, &g, k_max);
+        return Ok(());
     }
 
-    let g: Polynomial<ModInt<P>> = Polynomial::new(&w);
+    if let Some(c) = config.factor {
+        if !is_prime(p) {
+            return Err(CliError::RequiresPrime("--factor".to_string()));
+        }
+        let c = Polynomial::new_constant(T::element(cli::reduce(c, p)));
+        print_factorization(&(f - c.clone()), Polynomial::print_f_of_x);
+        print_factorization(&(g - c), Polynomial::print_f_of_y);
+        return Ok(());
+    }
 
-    let s: SolutionSet<(ModInt<P>, ModInt<P>)> = solve_equation(&f, &g);
+    if config.discriminant {
+        if !is_prime(p) {
+            return Err(CliError::RequiresPrime("--discriminant".to_string()));
+        }
+        print_discriminant(&f, "f(x)", Polynomial::print_f_of_x);
+        print_discriminant(&g, "g(y)", Polynomial::print_f_of_y);
+        return Ok(());
+    }
 
-    println!(
-        "方程式 {} = {} の解の集合は",
-        f.print_f_of_x(),
-        g.print_f_of_y()
-    );
-    print_solutions(&s);
+    // 指定がなければ、値の表が大きくなりすぎない範囲では表を使う
+    let method = config.method.unwrap_or(if T::cardinality() <= TABLE_LIMIT {
+        Method::Table
+    } else {
+        Method::Roots
+    });
+    if method == Method::Roots && !is_prime(p) {
+        return Err(CliError::RequiresPrime("--method roots".to_string()));
+    }
+    // 値の表は F_q の元の個数だけメモリを使う
+    if method == Method::Table && T::cardinality() > TABLE_LIMIT {
+        return Err(CliError::TooLarge("--method table".to_string()));
+    }
+    if config.count && method == Method::Roots && T::cardinality() > ROOTS_COUNT_LIMIT {
+        return Err(CliError::TooLarge("--count".to_string()));
+    }
+    if config.singular && !is_prime(p) {
+        return Err(CliError::RequiresPrime("--singular".to_string()));
+    }
+    // 直線全体が特異点になる場合は、F_q の元の個数だけ特異点を並べることになる
+    if config.singular
+        && T::cardinality() > TABLE_LIMIT
+        && singular::has_singular_component(&f, &g)
+    {
+        return Err(CliError::TooLarge("--singular".to_string()));
+    }
+    // f, g が等しい定数なら、無限遠直線の q + 1 個の点がすべて曲線上にある
+    if config.projective && T::cardinality() > TABLE_LIMIT && f.deg() == 0 && f == g {
+        return Err(CliError::TooLarge("--projective".to_string()));
+    }
+
+    if config.count {
+        let n = match method {
+            Method::Table => count_solutions(&f, &g),
+            Method::Roots => solutions_by_roots(&f, &g).count() as u64,
+            Method::BruteForce => solve_equation_brute_force(&f, &g).size() as u64,
+        };
+        println!(
+            "方程式 {} = {} の解の個数は {} です。",
+            f.print_f_of_x(),
+            g.print_f_of_y(),
+            n
+        );
+        if config.projective {
+            let closure = ProjectiveClosure::new(&f, &g);
+            let infinity = closure.points_at_infinity().len() as u64;
+            println!(
+                "射影閉包 {} の点の個数は {}（うち無限遠の点 {} 個）です。",
+                closure.print(),
+                n + infinity,
+                infinity
+            );
+        }
+        if is_prime(p) {
+            print_hasse_weil(&f, &g, n);
+        }
+        if config.singular {
+            print_singular_points(&f, &g);
+        }
+        return Ok(());
+    }
+
+    let limit = config.limit.unwrap_or(u64::MAX) as usize;
+    let affine: Box<dyn Iterator<Item = (T, T)>> = match method {
+        Method::Table => Box::new(solutions(&f, &g)),
+        Method::Roots => Box::new(solutions_by_roots(&f, &g)),
+        Method::BruteForce => Box::new(solve_equation_brute_force(&f, &g).unwrap().into_iter()),
+    };
+    // 射影閉包の点を求める場合は、アフィンの点の後に無限遠の点を続ける
+    let infinity = if config.projective {
+        ProjectiveClosure::new(&f, &g).points_at_infinity()
+    } else {
+        Vec::new()
+    };
+    let iter = affine
+        .map(|(x, y)| ProjectivePoint::Affine(x, y))
+        .chain(infinity);
+
+    if config.stream {
+        print_stream(iter.take(limit));
+        return Ok(());
+    }
+
+    let s: SolutionSet<ProjectivePoint<T>> = iter.take(limit).collect();
+
+    if config.projective {
+        println!(
+            "方程式 {} = {} の射影閉包 {} の点の集合は",
+            f.print_f_of_x(),
+            g.print_f_of_y(),
+            ProjectiveClosure::new(&f, &g).print()
+        );
+    } else {
+        println!(
+            "方程式 {} = {} の解の集合は",
+            f.print_f_of_x(),
+            g.print_f_of_y()
+        );
+    }
+    println!("{}", s);
     println!("です。");
+    if config.singular {
+        print_singular_points(&f, &g);
+    }
+    Ok(())
 }
 
-/// 素数判定
-fn is_prime(n: u64) -> bool {
-    if n == 2 {
-        return true;
+/// 一般の2変数多項式の方程式 F(x, y) = 0 を解いて表示する
+fn solve_bivariate<T>(config: &Config, p: u64, h: &BivariatePolynomial<T>) -> Result<(), CliError>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Ord + fmt::Display,
+{
+    // f(x) = g(y) の形の方程式にしか使えない機能
+    let separated_only = [
+        (config.factor.is_some(), "--factor"),
+        (config.discriminant, "--discriminant"),
+        (config.zeta.is_some(), "--zeta"),
+        (config.singular, "--singular"),
+        (config.projective, "--projective"),
+        (config.method == Some(Method::Table), "--method table"),
+    ];
+    if let Some((_, name)) = separated_only.iter().find(|(used, _)| *used) {
+        return Err(CliError::RequiresSeparated(name.to_string()));
     }
-    if n % 2 == 0 {
-        return false;
+    let method = config.method.unwrap_or(if is_prime(p) {
+        Method::Roots
+    } else {
+        Method::BruteForce
+    });
+    if method == Method::Roots && !is_prime(p) {
+        return Err(CliError::RequiresPrime("--method roots".to_string()));
     }
-    if n == 0 || n == 1 {
+
+    if config.count {
+        let n = match method {
+            Method::BruteForce => solve_bivariate_brute_force(h).size() as u64,
+            _ => count_bivariate_solutions(h),
+        };
+        println!("方程式 {} = 0 の解の個数は {} です。", h, n);
+        return Ok(());
+    }
+
+    let limit = config.limit.unwrap_or(u64::MAX) as usize;
+    let iter: Box<dyn Iterator<Item = (T, T)>> = match method {
+        Method::BruteForce => Box::new(solve_bivariate_brute_force(h).unwrap().into_iter()),
+        _ => Box::new(bivariate_solutions(h)),
+    };
+    let iter = iter.map(|(x, y)| ProjectivePoint::Affine(x, y));
+
+    if config.stream {
+        print_stream(iter.take(limit));
+        return Ok(());
+    }
+
+    let s: SolutionSet<ProjectivePoint<T>> = iter.take(limit).collect();
+    println!("方程式 {} = 0 の解の集合は", h);
+    println!("{}", s);
+    println!("です。");
+    Ok(())
+}
+
+/// 連立方程式 F_1(x, y) = 0, ..., F_k(x, y) = 0 の共通解を求めて表示する
+fn solve_system<T>(
+    config: &Config,
+    p: u64,
+    equations: &[BivariatePolynomial<T>],
+) -> Result<(), CliError>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Ord + fmt::Display,
+{
+    // 方程式が1つの場合にしか使えない機能
+    let single_only = [
+        (config.factor.is_some(), "--factor"),
+        (config.discriminant, "--discriminant"),
+        (config.zeta.is_some(), "--zeta"),
+        (config.singular, "--singular"),
+        (config.projective, "--projective"),
+        (config.method == Some(Method::Table), "--method table"),
+    ];
+    if let Some((_, name)) = single_only.iter().find(|(used, _)| *used) {
+        return Err(CliError::RequiresSingleEquation(name.to_string()));
+    }
+    let method = config.method.unwrap_or(if is_prime(p) {
+        Method::Roots
+    } else {
+        Method::BruteForce
+    });
+    if method == Method::Roots && !is_prime(p) {
+        return Err(CliError::RequiresPrime("--method roots".to_string()));
+    }
+
+    let s = match method {
+        Method::BruteForce => system::solve_system_brute_force(equations),
+        _ => system::solve_system(equations),
+    };
+    let names: Vec<String> = equations.iter().map(|h| format!("{} = 0", h)).collect();
+    if config.count {
+        println!("連立方程式 {} の解の個数は {} です。", names.join(", "), s.size());
+        return Ok(());
+    }
+
+    let limit = config.limit.unwrap_or(u64::MAX) as usize;
+    let iter = s.iter().map(|&(x, y)| ProjectivePoint::Affine(x, y));
+    if config.stream {
+        print_stream(iter.take(limit));
+        return Ok(());
+    }
+
+    let s: SolutionSet<ProjectivePoint<T>> = iter.take(limit).collect();
+    println!("連立方程式 {} の解の集合は", names.join(", "));
+    println!("{}", s);
+    println!("です。");
+    Ok(())
+}
+
+/// ```--sum```で与えた n 変数の方程式 f_1(x_1) + ... + f_n(x_n) = c を解いて表示する
+fn solve_multivariate<T>(config: &Config, p: u64) -> Result<(), CliError>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Ord + fmt::Display,
+{
+    // f(x) = g(y) の形の方程式にしか使えない機能
+    let separated_only = [
+        (config.factor.is_some(), "--factor"),
+        (config.discriminant, "--discriminant"),
+        (config.zeta.is_some(), "--zeta"),
+        (config.singular, "--singular"),
+        (config.projective, "--projective"),
+        (config.method == Some(Method::Roots), "--method roots"),
+    ];
+    if let Some((_, name)) = separated_only.iter().find(|(used, _)| *used) {
+        return Err(CliError::RequiresSeparated(name.to_string()));
+    }
+    let method = config.method.unwrap_or(Method::Table);
+    // 値の分布の表を作る。解の個数の合計 q^n は u64 に収まる必要がある。
+    let q = T::cardinality();
+    if q.checked_pow(config.sums.len() as u32).is_none()
+        || (method == Method::Table && q > TABLE_LIMIT)
+    {
+        return Err(CliError::TooLarge("--sum".to_string()));
+    }
+
+    let fs: Vec<Polynomial<T>> = config.sums.iter().map(|v| to_polynomial(v, p)).collect();
+    let c = T::element(cli::reduce(config.value.unwrap_or(0), p));
+    let terms: Vec<String> = fs
+        .iter()
+        .enumerate()
+        .map(|(i, f)| f.print_with_variable(&format!("x_{}", i + 1)))
+        .collect();
+    let name = format!("{} = {}", terms.join(" + "), c);
+
+    if config.count {
+        let n = match method {
+            Method::BruteForce => solve_multivariate_brute_force(&fs, c).size() as u64,
+            _ => count_multivariate_solutions(&fs, c),
+        };
+        println!("方程式 {} の解の個数は {} です。", name, n);
+        return Ok(());
+    }
+
+    let limit = config.limit.unwrap_or(u64::MAX) as usize;
+    let iter: Box<dyn Iterator<Item = Point<T>>> = match method {
+        Method::BruteForce => {
+            Box::new(solve_multivariate_brute_force(&fs, c).unwrap().into_iter())
+        }
+        _ => Box::new(multivariate_solutions(&fs, c)),
+    };
+    if config.stream {
+        print_stream(iter.take(limit));
+        return Ok(());
+    }
+
+    let s: SolutionSet<Point<T>> = iter.take(limit).collect();
+    println!("方程式 {} の解の集合は", name);
+    println!("{}", s);
+    println!("です。");
+    Ok(())
+}
+
+/// 解を集めずに1行に1つずつ順に書き出す。書き込めなくなったら（パイプが閉じられたなど）そこで止める。
+fn print_stream<T: fmt::Display>(iter: impl Iterator<Item = T>) {
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::new(stdout.lock());
+    for point in iter {
+        if writeln!(out, "{}", point).is_err() {
+            return;
+        }
+    }
+    out.flush().ok();
+}
+
+/// 判別式と、h = c が重根を持つ c（例外的なファイバーを持つ値）ごとの相異なる根の個数を表示する
+fn print_discriminant<T>(h: &Polynomial<T>, name: &str, print: fn(&Polynomial<T>) -> String)
+where
+    T: FiniteField + Convolution + Identity + Eq + Ord + fmt::Display,
+{
+    println!("{} = {} の判別式は {} です。", name, print(h), Polynomial::discriminant(h));
+    if h.deg() > 0 && Polynomial::derivative(h).is_zero() {
+        println!("  微分が 0 なので、すべての c で {} = c は重根を持ちます。", name);
+        return;
+    }
+    let values = Polynomial::critical_values(h);
+    if values.is_empty() {
+        println!("  {} = c が重根を持つ c はありません。", name);
+        return;
+    }
+    println!("  {} = c が重根を持つ c:", name);
+    for c in values {
+        let roots = (h.clone() - Polynomial::new_constant(c)).distinct_roots();
+        println!("    c = {}: 相異なる根 {} 個", c, roots.len());
+    }
+}
+
+/// 多項式の次数と各係数を対話的に入力する
+fn read_coefficients<R: BufRead>(input: &mut R, name: &str) -> Result<Vec<i128>, CliError> {
+    println!("{}の次数を入力", name);
+    let n = cli::parse_degree(&cli::read_line(input)?)?;
+    let mut v: Vec<i128> = Vec::new();
+    for i in 0..=n {
+        println!("{}次の係数を入力", i);
+        v.push(cli::parse_coefficient(&cli::read_line(input)?)?);
+    }
+    Ok(v)
+}
+
+/// 整数の係数列を mod p で簡約して多項式にする
+fn to_polynomial<T>(v: &[i128], p: u64) -> Polynomial<T>
+where
+    T: FiniteRing + Zero + Identity + Copy + Eq,
+{
+    let w: Vec<T> = v.iter().map(|&c| T::element(cli::reduce(c, p))).collect();
+    Polynomial::new(&w)
+}
+
+/// 素数判定（Miller-Rabin 法）。以下の底で ```u64``` の範囲では確定的に判定できる。
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
         return false;
     }
-    for i in 0..n {
-        if n != 3 + 2 * i && n % (3 + 2 * i) == 0 {
-            return false;
+    for &b in &BASES {
+        if n.is_multiple_of(b) {
+            return n == b;
         }
-        if (3 + 2 * i) * (3 + 2 * i) >= n {
-            break;
+    }
+    let mut d = n - 1;
+    let mut s = 0;
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+    'bases: for &b in &BASES {
+        let mut x = modpow(b, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
         }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
     }
     true
 }
 
-/// 方程式の解を全探索
-fn solve_equation(
-    f: &Polynomial<ModInt<P>>,
-    g: &Polynomial<ModInt<P>>,
-) -> SolutionSet<(ModInt<P>, ModInt<P>)> {
-    let mut s: HashSet<(ModInt<P>, ModInt<P>)> = HashSet::new();
-    for i in 0..P {
-        for j in 0..P {
-            if Polynomial::evaluate(&f, ModInt::<P>::new(i))
-                == Polynomial::evaluate(&g, ModInt::<P>::new(j))
-            {
-                s.insert((ModInt::<P>::new(i), ModInt::<P>::new(j)));
+/// ```a^e mod m```
+fn modpow(a: u64, e: u64, m: u64) -> u64 {
+    let mut res = 1;
+    let mut a = a % m;
+    let mut e = e;
+    while e > 0 {
+        if e % 2 == 1 {
+            res = mul_mod(res, a, m);
+        }
+        a = mul_mod(a, a, m);
+        e /= 2;
+    }
+    res
+}
+
+/// アフィン特異点とその重複度、接錐を表示する
+fn print_singular_points<T>(f: &Polynomial<T>, g: &Polynomial<T>)
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord + fmt::Display,
+{
+    if f.deg() == 0 && g.deg() == 0 {
+        println!("f, g がともに定数なので曲線を定めず、特異点は求めません。");
+        return;
+    }
+    let points = singular::singular_points(f, g);
+    if points.is_empty() {
+        println!("アフィン特異点はありません（アフィン部分は非特異です）。");
+        return;
+    }
+    println!("アフィン特異点（u = x - x_0, v = y - y_0）:");
+    for point in &points {
+        let lines: Vec<String> = point
+            .tangent_cone
+            .tangent_lines()
+            .iter()
+            .map(|(du, dv, k)| {
+                if *k > 1 {
+                    format!("({}, {})^{}", du, dv, k)
+                } else {
+                    format!("({}, {})", du, dv)
+                }
+            })
+            .collect();
+        println!(
+            "  ({}, {}): 重複度 {}、接錐 {}、F_q 上の接線の方向 {}{}",
+            point.x,
+            point.y,
+            point.multiplicity,
+            point.tangent_cone,
+            if lines.is_empty() {
+                "なし".to_string()
+            } else {
+                lines.join(", ")
+            },
+            if point.tangent_cone.is_ordinary() {
+                "（通常特異点）"
+            } else {
+                ""
             }
+        );
+    }
+}
+
+/// 解の個数 N について Hasse–Weil の評価を確かめた結果を表示する
+fn print_hasse_weil<T>(f: &Polynomial<T>, g: &Polynomial<T>, n: u64)
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    let report = match hasse_weil::hasse_weil_check(f, g, n) {
+        Some(report) => report,
+        None => {
+            println!("種数が求められないので Hasse–Weil の評価は確かめられません。");
+            return;
         }
+    };
+    println!(
+        "種数 g = {}、無限遠の点 {} 個、特異点 {} 個",
+        report.genus, report.points_at_infinity, report.singular_points
+    );
+    println!(
+        "|N - q| = {} <= 2g√q + {} = {:.2}: {}",
+        report.deviation().abs(),
+        report.correction,
+        report.bound(),
+        if report.holds() { "成立" } else { "不成立" }
+    );
+    if !report.holds() {
+        eprintln!("注：解の個数が Hasse–Weil の評価を超えています（曲線が可約である可能性があります）。");
     }
-    SolutionSet::new(s)
 }
 
-fn print_solutions(ss: &SolutionSet<(ModInt<P>, ModInt<P>)>) {
-    let mut s: String = String::new();
-    if ss.size() == 0 {
-        s.push_str(&"{ }");
-    } else {
-        s.push_str(&"{");
-        for (x, y) in &ss.unwrap() {
-            s.push_str(&"(");
-            s.push_str(&format!("{}", x).to_string());
-            s.push_str(&", ");
-            s.push_str(&format!("{}", y).to_string());
-            s.push_str(&")");
-            s.push_str(&", ");
-        }
-        s.pop();
-        s.pop();
-        s.push_str(&"}");
+/// F_p 上の曲線 f(x) = g(y) の点の個数 N_1, ..., N_K と L(T) を表示する
+fn print_zeta<T>(p: u64, f: &Polynomial<T>, g: &Polynomial<T>, k_max: usize)
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    let fv: Vec<u64> = f.coefficients().iter().map(FiniteRing::index).collect();
+    let gv: Vec<u64> = g.coefficients().iter().map(FiniteRing::index).collect();
+    let counts = zeta::point_counts(p, &fv, &gv, k_max);
+    let singular = singular::singular_points(f, g).len();
+    if singular > 0 {
+        eprintln!("注：F_p 上に特異点が {} 個あるので、N_k は正しくない可能性があります。", singular);
+    }
+    println!("射影非特異モデルの点の個数（アフィン部分が非特異であると仮定）:");
+    for (k, n) in counts.iter().enumerate() {
+        println!("  N_{} = {}", k + 1, n);
+    }
+    let genus = match zeta::genus(f.deg(), g.deg(), p) {
+        Some(genus) => genus,
+        None => {
+            println!("この曲線の種数は求められません。");
+            return;
+        }
+    };
+    println!("種数 g = {}", genus);
+    let l = match zeta::l_polynomial(p, genus, &counts) {
+        Some(l) => l,
+        None => {
+            println!("L(T) を求めるには K >= {} が必要です。", genus);
+            return;
+        }
+    };
+    println!("L(T) = {}", zeta::print_l_polynomial(&l));
+    // g より大きい k の N_k は L(T) から決まるので、数えた値と比べて仮定を確かめる
+    let expected = zeta::counts_from_l_polynomial(p, &l, k_max);
+    if counts.iter().zip(&expected).any(|(&n, &e)| n as i128 != e) {
+        eprintln!("注：N_k が L(T) と合いません。曲線が非特異でない可能性があります。");
+    }
+}
+
+/// ```h```の既約因子分解と、相異なる根の個数（h = f - c なら c のファイバーの大きさ）を表示する
+fn print_factorization<T>(h: &Polynomial<T>, print: fn(&Polynomial<T>) -> String)
+where
+    T: FiniteField + Convolution + Identity + Eq + Ord + fmt::Display,
+{
+    if h.is_zero() {
+        println!("{} = 0", print(h));
+        return;
+    }
+    let factors = h.factor();
+    let mut s = String::new();
+    let lc = h.leading_coefficient();
+    if factors.is_empty() || lc != T::identity() {
+        s.push_str(&lc.to_string());
+    }
+    for (factor, m) in &factors {
+        if !s.is_empty() {
+            s.push(' ');
+        }
+        s.push('(');
+        s.push_str(&print(factor));
+        s.push(')');
+        if *m > 1 {
+            s.push('^');
+            s.push_str(&m.to_string());
+        }
+    }
+    let n = factors.iter().filter(|(factor, _)| factor.deg() == 1).count();
+    println!("{} = {}", print(h), s);
+    println!("  相異なる根の個数: {}", n);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2^26 より大きい素数
+    const LARGE_PRIME: &str = "1000000000039";
+
+    fn run_with(args: &[&str]) -> Result<(), CliError> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        run(&args)
+    }
+
+    fn too_large(option: &str) -> Result<(), CliError> {
+        Err(CliError::TooLarge(option.to_string()))
+    }
+
+    #[test]
+    fn large_fields_reject_table_and_counting_by_roots() {
+        let curve = ["--f", "0,1", "--g", "0,0,1"];
+        let table = [&["-p", LARGE_PRIME, "--method", "table"][..], &curve].concat();
+        assert_eq!(run_with(&table), too_large("--method table"));
+        let count = [&["-p", LARGE_PRIME, "-c"][..], &curve].concat();
+        assert_eq!(run_with(&count), too_large("--count"));
+        assert_eq!(
+            run_with(&["-p", "6", "--method", "roots", "--f", "0,1", "--g", "0,1"]),
+            Err(CliError::RequiresPrime("--method roots".to_string()))
+        );
+    }
+
+    #[test]
+    fn factor_requires_prime() {
+        assert_eq!(
+            run_with(&["-p", "6", "--factor", "0", "--f", "0,1", "--g", "0,1"]),
+            Err(CliError::RequiresPrime("--factor".to_string()))
+        );
+    }
+
+    #[test]
+    fn extension_requires_prime() {
+        assert_eq!(
+            run_with(&["-p", "6", "--extension", "2", "--f", "0,1", "--g", "0,1"]),
+            Err(CliError::RequiresPrime("--extension".to_string()))
+        );
+        assert_eq!(
+            run_with(&["-p", "9", "--modulus", "1,0,1", "--f", "0,1", "--g", "0,1"]),
+            Err(CliError::RequiresPrime("--modulus".to_string()))
+        );
+    }
+
+    #[test]
+    fn zeta_rejects_huge_extension_degree() {
+        // 2^(2^32 + 1) は u32 に切り詰めると 2^1 になってしまう
+        let curve = ["-p", "2", "--f", "0,1", "--g", "0,0,1", "--zeta"];
+        for k in &["27", "4294967297", "18446744073709551615"] {
+            assert_eq!(run_with(&[&curve[..], &[k]].concat()), too_large("--zeta"));
+        }
+    }
+
+    #[test]
+    fn projective_rejects_whole_line_at_infinity_of_large_field() {
+        let args = [
+            "-p",
+            LARGE_PRIME,
+            "--f",
+            "1",
+            "--g",
+            "1",
+            "--projective",
+            "--limit",
+            "1",
+        ];
+        assert_eq!(run_with(&args), too_large("--projective"));
+    }
+
+    #[test]
+    fn singular_rejects_singular_line_of_large_field() {
+        // x^2 = 0 は直線 x = 0 の全体が特異点になる
+        let args = ["-p", LARGE_PRIME, "--f", "0,0,1", "--g", "0", "--singular"];
+        assert_eq!(run_with(&args), too_large("--singular"));
     }
-    println!("{}", s);
 }
@@ -1,10 +1,58 @@
+use crate::finite_ring::{FiniteField, FiniteRing};
 use crate::identities::{Identity, Zero};
 use crate::inverse::Inverse;
 use std::fmt;
 use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
+/// ```0```以上```m```未満の```a```と```b```について```(a + b) mod m```を求める。
+/// ```m```が```2^63```を超えても溢れないようにしている。
+pub(crate) fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    let (s, overflow) = a.overflowing_add(b);
+    if overflow || s >= m {
+        s.wrapping_sub(m)
+    } else {
+        s
+    }
+}
+
+/// ```0```以上```m```未満の```a```と```b```について```(a - b) mod m```を求める。
+pub(crate) fn sub_mod(a: u64, b: u64, m: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        a + (m - b)
+    }
+}
+
+/// ```(a * b) mod m```を求める。積は128ビットで計算するので溢れない。
+pub(crate) fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// 拡張ユークリッドの互除法で```a```の```m```を法とする逆元を求める。
+/// ```gcd(a, m) != 1```なら```None```。```m```が合成数でも正しい。
+pub(crate) fn inverse_mod(a: u64, m: u64) -> Option<u64> {
+    // r0 = s0 * a (mod m), r1 = s1 * a (mod m) を保ちながら割っていく
+    let (mut r0, mut r1) = (m as i128, (a % m) as i128);
+    let (mut s0, mut s1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        let r = r0 - q * r1;
+        r0 = r1;
+        r1 = r;
+        let s = s0 - q * s1;
+        s0 = s1;
+        s1 = s;
+    }
+    if r0 != 1 {
+        None
+    } else {
+        Some(s0.rem_euclid(m as i128) as u64)
+    }
+}
+
 /// ```MOD```で割った余り。Z / MOD Z の元。
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct ModInt<const MOD: u64> {
     representative: u64,
 }
@@ -32,10 +80,10 @@ impl<const MOD: u64> ModInt<MOD> {
                 break;
             }
             if m % 2 == 1 {
-                res = (res * a) % MOD;
+                res = mul_mod(res, a, MOD);
             }
-            a = (a * a) % MOD;
-            m = m / 2;
+            a = mul_mod(a, a, MOD);
+            m /= 2;
         }
         ModInt {
             representative: res,
@@ -54,7 +102,7 @@ impl<const MOD: u64> Add for ModInt<MOD> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
         ModInt {
-            representative: (self.representative + rhs.representative) % MOD,
+            representative: add_mod(self.representative, rhs.representative, MOD),
         }
     }
 }
@@ -62,7 +110,7 @@ impl<const MOD: u64> Add for ModInt<MOD> {
 impl<const MOD: u64> AddAssign for ModInt<MOD> {
     fn add_assign(&mut self, other: Self) {
         *self = Self {
-            representative: (self.representative + other.representative) % MOD,
+            representative: add_mod(self.representative, other.representative, MOD),
         };
     }
 }
@@ -71,7 +119,7 @@ impl<const MOD: u64> Sub for ModInt<MOD> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
         ModInt {
-            representative: (self.representative + MOD - rhs.representative) % MOD,
+            representative: sub_mod(self.representative, rhs.representative, MOD),
         }
     }
 }
@@ -79,7 +127,7 @@ impl<const MOD: u64> Sub for ModInt<MOD> {
 impl<const MOD: u64> SubAssign for ModInt<MOD> {
     fn sub_assign(&mut self, other: Self) {
         *self = Self {
-            representative: (self.representative + MOD - other.representative) % MOD,
+            representative: sub_mod(self.representative, other.representative, MOD),
         };
     }
 }
@@ -88,7 +136,7 @@ impl<const MOD: u64> Mul for ModInt<MOD> {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self::Output {
         ModInt {
-            representative: (self.representative * rhs.representative) % MOD,
+            representative: mul_mod(self.representative, rhs.representative, MOD),
         }
     }
 }
@@ -96,7 +144,7 @@ impl<const MOD: u64> Mul for ModInt<MOD> {
 impl<const MOD: u64> MulAssign for ModInt<MOD> {
     fn mul_assign(&mut self, other: Self) {
         *self = Self {
-            representative: (self.representative * other.representative) % MOD,
+            representative: mul_mod(self.representative, other.representative, MOD),
         };
     }
 }
@@ -121,14 +169,132 @@ impl<const MOD: u64> Identity for ModInt<MOD> {
     }
 }
 
+/// 拡張ユークリッドの互除法で求めるので、```MOD```が合成数でも正しい逆元を返す。
 impl<const MOD: u64> Inverse for ModInt<MOD> {
     fn inverse(self) -> Option<ModInt<MOD>> {
-        let n = self.to_int();
-        if num::Integer::gcd(&n, &MOD) != 1 {
-            None
-        } else {
-            let ret = self.modpow(MOD - 2);
-            Some(ret)
+        inverse_mod(self.representative, MOD).map(|n| ModInt { representative: n })
+    }
+}
+
+impl<const MOD: u64> FiniteRing for ModInt<MOD> {
+    fn cardinality() -> u64 {
+        MOD
+    }
+
+    fn element(i: u64) -> Self {
+        ModInt::new(i)
+    }
+
+    fn index(&self) -> u64 {
+        self.representative
+    }
+}
+
+/// ```MOD```が素数の場合に限り有限体になる。
+impl<const MOD: u64> FiniteField for ModInt<MOD> {}
+
+/// 2^64 未満で最大の素数
+#[cfg(test)]
+pub(crate) const BIG: u64 = 18_446_744_073_709_551_557;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_near_u64_max() {
+        let a = ModInt::<BIG>::new(BIG - 1);
+        let b = ModInt::<BIG>::new(BIG - 2);
+        assert_eq!((a + b).to_int(), BIG - 3);
+        assert_eq!((a * a).to_int(), 1);
+        assert_eq!((a * b).to_int(), 2);
+        assert_eq!((ModInt::<BIG>::new(0) - a).to_int(), 1);
+        assert_eq!((-b).to_int(), 2);
+        let mut c = a;
+        c += b;
+        c *= a;
+        c -= b;
+        assert_eq!(c.to_int(), 5);
+    }
+
+    #[test]
+    fn multiplication_matches_u128() {
+        let xs = [1, 2, 3, 1 << 32, (1 << 63) + 12345, BIG - 59, BIG - 1];
+        for &x in &xs {
+            for &y in &xs {
+                let expected = (x as u128 * y as u128 % BIG as u128) as u64;
+                assert_eq!(
+                    (ModInt::<BIG>::new(x) * ModInt::<BIG>::new(y)).to_int(),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fermat_near_u64_max() {
+        for &x in &[2, 3, 1 << 40, BIG - 1, BIG / 3] {
+            let a = ModInt::<BIG>::new(x);
+            assert_eq!(a.modpow(BIG - 1).to_int(), 1);
+            assert_eq!((a * a.inverse().unwrap()).to_int(), 1);
+        }
+    }
+
+    /// ```MOD```の単位元はすべて逆元をもち、それ以外は```None```になることを確かめる
+    fn check_inverses<const MOD: u64>() {
+        for n in 0..MOD {
+            let a = ModInt::<MOD>::new(n);
+            match a.inverse() {
+                Some(b) => {
+                    assert_eq!(num::Integer::gcd(&n, &MOD), 1);
+                    assert_eq!(a * b, ModInt::<MOD>::identity(), "{} mod {}", n, MOD);
+                }
+                None => assert_ne!(num::Integer::gcd(&n, &MOD), 1, "{} mod {}", n, MOD),
+            }
         }
     }
+
+    #[test]
+    fn inverses_for_prime_moduli() {
+        check_inverses::<2>();
+        check_inverses::<7>();
+        check_inverses::<101>();
+    }
+
+    #[test]
+    fn inverses_for_composite_moduli() {
+        check_inverses::<1>();
+        check_inverses::<4>();
+        check_inverses::<12>();
+        check_inverses::<100>();
+        check_inverses::<561>();
+        check_inverses::<1001>();
+        check_inverses::<1024>();
+        check_inverses::<3600>();
+    }
+
+    #[test]
+    fn inverses_for_large_composite_modulus() {
+        const M: u64 = u64::MAX;
+        for &n in &[2, 7, 1 << 40, M - 1, M - 2, 1_234_567_891] {
+            let a = ModInt::<M>::new(n);
+            assert_eq!((a * a.inverse().unwrap()).to_int(), 1);
+        }
+        // 3 と 5 は u64::MAX を割り切る
+        assert!(ModInt::<M>::new(3).inverse().is_none());
+        assert!(ModInt::<M>::new(5 * 17).inverse().is_none());
+        assert!(ModInt::<M>::new(0).inverse().is_none());
+    }
+
+    #[test]
+    fn composite_modulus_u64_max() {
+        const M: u64 = u64::MAX;
+        let a = ModInt::<M>::new(M - 1);
+        assert_eq!((a + a).to_int(), M - 2);
+        assert_eq!((a * a).to_int(), 1);
+        assert_eq!(
+            (ModInt::<M>::new(1 << 63) * ModInt::<M>::new(2)).to_int(),
+            1
+        );
+    }
 }
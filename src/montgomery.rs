@@ -0,0 +1,311 @@
+use crate::finite_ring::{FiniteField, FiniteRing};
+use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
+use crate::modint::{add_mod, sub_mod, ModInt};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Montgomery 表現で持つ Z / MOD Z の元。```MOD```は奇数とする。
+///
+/// R = 2^64 として、剰余類 a を ```a * R mod MOD``` の形で格納する。
+/// 掛け算の剰余を割り算なしで計算できるので、```ModInt```より速い。
+/// 値の比較や表示は通常の代表元（```0```以上```MOD```未満）で行う。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct MontgomeryModInt<const MOD: u64> {
+    value: u64,
+}
+
+impl<const MOD: u64> MontgomeryModInt<MOD> {
+    /// ```MOD```が奇数であることの確認
+    const CHECK: () = assert!(MOD % 2 == 1, "Montgomery 表現の法は奇数である必要があります");
+
+    /// ```MOD * INV ≡ 1 (mod 2^64)```となる```INV```。ニュートン法で求める。
+    const INV: u64 = {
+        let mut inv = MOD;
+        let mut i = 0;
+        while i < 6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(MOD.wrapping_mul(inv)));
+            i += 1;
+        }
+        inv
+    };
+
+    /// ```R^2 mod MOD```
+    const R2: u64 = {
+        let r = ((u64::MAX % MOD) as u128 + 1) % MOD as u128;
+        (r * r % MOD as u128) as u64
+    };
+
+    /// ```t * R^{-1} mod MOD```を求める（Montgomery reduction）。```t < MOD * 2^64```とする。
+    fn reduce(t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(Self::INV);
+        let mh = ((m as u128 * MOD as u128) >> 64) as u64;
+        let th = (t >> 64) as u64;
+        // t - m * MOD の下位64ビットは0なので、上位だけ引けばよい
+        if th >= mh {
+            th - mh
+        } else {
+            th.wrapping_sub(mh).wrapping_add(MOD)
+        }
+    }
+
+    /// コンストラクタ。代表元```n mod MOD```を Montgomery 表現に直して格納。
+    pub fn new(n: u64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHECK;
+        MontgomeryModInt {
+            value: Self::reduce((n % MOD) as u128 * Self::R2 as u128),
+        }
+    }
+
+    /// ```u64```型にする（通常の代表元を返す）
+    pub fn to_int(&self) -> u64 {
+        Self::reduce(self.value as u128)
+    }
+
+    /// 繰り返し二乗法によるべき乗の計算
+    pub fn modpow(&self, n: u64) -> Self {
+        let mut res = Self::identity();
+        let mut a = *self;
+        let mut m = n;
+        while m > 0 {
+            if m % 2 == 1 {
+                res *= a;
+            }
+            a *= a;
+            m /= 2;
+        }
+        res
+    }
+}
+
+impl<const MOD: u64> From<ModInt<MOD>> for MontgomeryModInt<MOD> {
+    fn from(a: ModInt<MOD>) -> Self {
+        MontgomeryModInt::new(a.to_int())
+    }
+}
+
+impl<const MOD: u64> From<MontgomeryModInt<MOD>> for ModInt<MOD> {
+    fn from(a: MontgomeryModInt<MOD>) -> Self {
+        ModInt::new(a.to_int())
+    }
+}
+
+/// 順序は通常の代表元の大小で決める。
+impl<const MOD: u64> Ord for MontgomeryModInt<MOD> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_int().cmp(&other.to_int())
+    }
+}
+
+impl<const MOD: u64> PartialOrd for MontgomeryModInt<MOD> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<const MOD: u64> fmt::Display for MontgomeryModInt<MOD> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_int())
+    }
+}
+
+impl<const MOD: u64> Add for MontgomeryModInt<MOD> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        MontgomeryModInt {
+            value: add_mod(self.value, rhs.value, MOD),
+        }
+    }
+}
+
+impl<const MOD: u64> AddAssign for MontgomeryModInt<MOD> {
+    fn add_assign(&mut self, other: Self) {
+        self.value = add_mod(self.value, other.value, MOD);
+    }
+}
+
+impl<const MOD: u64> Sub for MontgomeryModInt<MOD> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        MontgomeryModInt {
+            value: sub_mod(self.value, rhs.value, MOD),
+        }
+    }
+}
+
+impl<const MOD: u64> SubAssign for MontgomeryModInt<MOD> {
+    fn sub_assign(&mut self, other: Self) {
+        self.value = sub_mod(self.value, other.value, MOD);
+    }
+}
+
+impl<const MOD: u64> Mul for MontgomeryModInt<MOD> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        MontgomeryModInt {
+            value: Self::reduce(self.value as u128 * rhs.value as u128),
+        }
+    }
+}
+
+impl<const MOD: u64> MulAssign for MontgomeryModInt<MOD> {
+    fn mul_assign(&mut self, other: Self) {
+        self.value = Self::reduce(self.value as u128 * other.value as u128);
+    }
+}
+
+/// unary negation の実装
+impl<const MOD: u64> Neg for MontgomeryModInt<MOD> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        MontgomeryModInt {
+            value: sub_mod(0, self.value, MOD),
+        }
+    }
+}
+
+impl<const MOD: u64> Zero for MontgomeryModInt<MOD> {
+    fn zero() -> Self {
+        MontgomeryModInt { value: 0 }
+    }
+}
+
+impl<const MOD: u64> Identity for MontgomeryModInt<MOD> {
+    fn identity() -> Self {
+        MontgomeryModInt::new(1)
+    }
+}
+
+/// 逆元は通常の代表元で```ModInt```として求めてから戻す。
+impl<const MOD: u64> Inverse for MontgomeryModInt<MOD> {
+    fn inverse(self) -> Option<Self> {
+        ModInt::<MOD>::from(self).inverse().map(Self::from)
+    }
+}
+
+impl<const MOD: u64> FiniteRing for MontgomeryModInt<MOD> {
+    fn cardinality() -> u64 {
+        MOD
+    }
+
+    fn element(i: u64) -> Self {
+        MontgomeryModInt::new(i)
+    }
+
+    fn index(&self) -> u64 {
+        self.to_int()
+    }
+}
+
+/// ```MOD```が素数の場合に限り有限体になる。
+impl<const MOD: u64> FiniteField for MontgomeryModInt<MOD> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::modint::BIG;
+    use crate::random::Random;
+
+    /// ランダムな値について、演算の結果が```ModInt```と```DynModInt```に一致することを確かめる
+    fn check_against_modint<const MOD: u64>() {
+        let _context = DynModContext::new(MOD);
+        let mut random = Random::new(MOD);
+        let mut values: Vec<u64> = vec![0, 1, 2, MOD - 1, MOD / 2, MOD.wrapping_add(1)];
+        values.extend((0..50).map(|_| random.next_u64()));
+        for &x in &values {
+            let (a, a_mod, a_dyn) = (
+                MontgomeryModInt::<MOD>::new(x),
+                ModInt::<MOD>::new(x),
+                DynModInt::new(x),
+            );
+            assert_eq!(a.to_int(), a_mod.to_int(), "{} mod {}", x, MOD);
+            assert_eq!(ModInt::from(a), a_mod);
+            assert_eq!(MontgomeryModInt::from(a_mod), a);
+            assert_eq!((-a).to_int(), (-a_mod).to_int());
+            assert_eq!(
+                a.inverse().map(|b| b.to_int()),
+                a_mod.inverse().map(|b| b.to_int())
+            );
+            assert_eq!(
+                a.inverse().map(|b| b.to_int()),
+                a_dyn.inverse().map(|b| b.to_int())
+            );
+            let e = random.next_u64();
+            assert_eq!(
+                a.modpow(e).to_int(),
+                a_mod.modpow(e).to_int(),
+                "{}^{} mod {}",
+                x,
+                e,
+                MOD
+            );
+            assert_eq!(
+                a.modpow(e).to_int(),
+                a_dyn.modpow(e).to_int(),
+                "{}^{} mod {}",
+                x,
+                e,
+                MOD
+            );
+            for &y in values.iter().take(10) {
+                let (b, b_mod, b_dyn) = (
+                    MontgomeryModInt::<MOD>::new(y),
+                    ModInt::<MOD>::new(y),
+                    DynModInt::new(y),
+                );
+                assert_eq!((a + b).to_int(), (a_mod + b_mod).to_int());
+                assert_eq!((a - b).to_int(), (a_mod - b_mod).to_int());
+                assert_eq!((a * b).to_int(), (a_mod * b_mod).to_int());
+                assert_eq!((a * b).to_int(), (a_dyn * b_dyn).to_int());
+                assert_eq!(a.cmp(&b), a_mod.cmp(&b_mod));
+                let mut c = a;
+                c += b;
+                c *= b;
+                c -= a;
+                assert_eq!(c.to_int(), ((a_mod + b_mod) * b_mod - a_mod).to_int());
+            }
+        }
+    }
+
+    #[test]
+    fn matches_modint_for_various_moduli() {
+        check_against_modint::<3>();
+        check_against_modint::<998_244_353>();
+        check_against_modint::<1_000_000_007>();
+        check_against_modint::<BIG>();
+        // 合成数の法でも、逆元以外は同じ
+        check_against_modint::<{ u64::MAX }>();
+        check_against_modint::<1_000_000_000_000_000_001>();
+    }
+
+    #[test]
+    fn identities_and_display() {
+        type M = MontgomeryModInt<BIG>;
+        assert_eq!(M::zero().to_int(), 0);
+        assert_eq!(M::identity().to_int(), 1);
+        assert_eq!(M::new(BIG + 5), M::new(5));
+        assert_eq!(M::new(BIG - 1).to_string(), (BIG - 1).to_string());
+        assert_eq!(M::element(7).index(), 7);
+        assert_eq!(M::cardinality(), BIG);
+    }
+
+    #[test]
+    fn fermat_and_inverse() {
+        for &x in &[2, 3, 1 << 40, BIG - 1, BIG / 3] {
+            let a = MontgomeryModInt::<BIG>::new(x);
+            assert_eq!(a.modpow(BIG - 1), MontgomeryModInt::identity());
+            assert_eq!(a * a.inverse().unwrap(), MontgomeryModInt::identity());
+        }
+        assert!(MontgomeryModInt::<BIG>::zero().inverse().is_none());
+        // 15 = 3 × 5 なので 3 は逆元を持たない
+        assert!(MontgomeryModInt::<15>::new(3).inverse().is_none());
+        assert_eq!(
+            MontgomeryModInt::<15>::new(7).inverse(),
+            Some(MontgomeryModInt::new(13))
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use crate::finite_ring::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::ntt::{can_use_ntt, convolve_mod};
+use crate::polynomial::Polynomial;
+use crate::solution_set::SolutionSet;
+use crate::solver::{value_counts, Fibers};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub};
+
+/// n 変数の点 (x_1, ..., x_n)。小さい順に並べると座標の辞書式順になる。
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Point<T>(pub Vec<T>);
+
+/// ```(x_1, x_2, x_3)```のように表示する。
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let coordinates: Vec<String> = self.0.iter().map(|x| x.to_string()).collect();
+        write!(f, "({})", coordinates.join(", "))
+    }
+}
+
+/// 各値（の番号）```c```について、f_1(x_1) + ... + f_n(x_n) = c となる (x_1, ..., x_n) の個数を
+/// 並べたもの。変数が無い場合は、値```0```だけが1個になる。
+///
+/// 各 f_i の```value_counts```を、足し算についての畳み込みで順に合わせる。
+/// 素体では元の番号の和を p で割った余りが和の番号になるので、巡回畳み込みを NTT で求められ、
+/// 全体で O(n・p log p)。NTT で正確に求められない大きさの場合や拡大体では、1回あたり O(q^2)。
+/// 個数の合計は q^n なので、q^n が```u64```に収まる必要がある。
+pub fn sum_value_counts<T>(fs: &[Polynomial<T>]) -> Vec<u64>
+where
+    T: FiniteField + Zero + Identity + Add<Output = T> + Mul<Output = T> + MulAssign + AddAssign,
+    T: Copy + Eq,
+{
+    let mut counts = vec![0; T::cardinality() as usize];
+    counts[T::zero().index() as usize] = 1;
+    for f in fs {
+        counts = additive_convolution::<T>(&counts, &value_counts(f));
+    }
+    counts
+}
+
+/// 値の分布```a```, ```b```（番号で添字付け）の、足し算についての畳み込み
+fn additive_convolution<T>(a: &[u64], b: &[u64]) -> Vec<u64>
+where
+    T: FiniteField + Add<Output = T> + Copy,
+{
+    let q = T::cardinality() as usize;
+    let prime_field = T::characteristic() == T::cardinality();
+    let m = a.iter().chain(b).copied().max().unwrap_or(0) + 1;
+    if prime_field && can_use_ntt(q, q, m) {
+        // 真の値は q^n 以下で u64::MAX 未満なので、u64::MAX を法としても変わらない
+        let mut c = convolve_mod(a, b, u64::MAX);
+        let (low, high) = c.split_at_mut(q);
+        for (x, &y) in low.iter_mut().zip(high.iter()) {
+            *x += y;
+        }
+        c.truncate(q);
+        return c;
+    }
+    let mut c = vec![0; q];
+    for (i, &s) in a.iter().enumerate() {
+        if s == 0 {
+            continue;
+        }
+        let x = T::element(i as u64);
+        for (j, &t) in b.iter().enumerate() {
+            if t == 0 {
+                continue;
+            }
+            let k = if prime_field {
+                (i + j) % q
+            } else {
+                (x + T::element(j as u64)).index() as usize
+            };
+            c[k] += s * t;
+        }
+    }
+    c
+}
+
+/// 方程式 f_1(x_1) + ... + f_n(x_n) = c の解の個数を、解を列挙せずに求める。
+///
+/// 計算量とメモリは```sum_value_counts```と同じ。
+pub fn count_multivariate_solutions<T>(fs: &[Polynomial<T>], c: T) -> u64
+where
+    T: FiniteField + Zero + Identity + Add<Output = T> + Mul<Output = T> + MulAssign + AddAssign,
+    T: Copy + Eq,
+{
+    sum_value_counts(fs)[c.index() as usize]
+}
+
+/// 方程式 f_1(x_1) + ... + f_n(x_n) = c の解を1つずつ返すイテレータ。
+/// ```multivariate_solutions```で作る。
+///
+/// 解は (x_1, ..., x_n) の番号の辞書式順に返す。後ろの変数の和の値の分布を前もって持ち、
+/// 解に続かない x_i は調べない。最後の変数は f_n のファイバーから選ぶので、
+/// n = 2 なら全体で O(q・deg + 解の個数)、一般には解1つあたり O(n・q・deg)。
+#[derive(Clone, Debug)]
+pub struct MultivariateSolutions<'a, T> {
+    fs: &'a [Polynomial<T>],
+    /// ```suffix[k][c]```は f_{k+1}(x_{k+1}) + ... + f_n(x_n) = c となる組の個数
+    suffix: Vec<Vec<u64>>,
+    /// f_n のファイバー
+    fibers: Fibers,
+    /// x_1, ..., x_{n-1} ごとに、次に調べる番号と、その変数以降の和が取るべき値
+    stack: Vec<(u64, T)>,
+    /// 今の x_1, ..., x_{n-1} に対して f_n(x_n) が取るべき値（の番号）と、ファイバーの中で次に返す位置
+    last: Option<(u64, usize)>,
+}
+
+impl<'a, T> Iterator for MultivariateSolutions<'a, T>
+where
+    T: FiniteField + Zero + Identity + Sub<Output = T> + Mul<Output = T> + MulAssign + AddAssign,
+    T: Copy + Eq,
+{
+    type Item = Point<T>;
+
+    fn next(&mut self) -> Option<Point<T>> {
+        loop {
+            if let Some((value, position)) = &mut self.last {
+                if let Some(&j) = self.fibers.fiber(*value).get(*position) {
+                    *position += 1;
+                    // スタックの番号は次に調べるものなので、今の値はその1つ前
+                    let mut point: Vec<T> =
+                        self.stack.iter().map(|&(i, _)| T::element(i - 1)).collect();
+                    point.push(T::element(j));
+                    return Some(Point(point));
+                }
+                self.last = None;
+            }
+            let &(i, rest) = self.stack.last()?;
+            let k = self.stack.len() - 1;
+            if i >= T::cardinality() {
+                self.stack.pop();
+                continue;
+            }
+            self.stack[k].0 += 1;
+            let remaining = rest - Polynomial::evaluate(&self.fs[k], T::element(i));
+            if self.suffix[k + 1][remaining.index() as usize] == 0 {
+                continue;
+            }
+            if k + 2 == self.fs.len() {
+                self.last = Some((remaining.index(), 0));
+            } else {
+                self.stack.push((0, remaining));
+            }
+        }
+    }
+}
+
+/// 方程式 f_1(x_1) + ... + f_n(x_n) = c の解を順に返すイテレータを作る。
+///
+/// 後ろの変数 f_k + ... + f_n の値の分布を```sum_value_counts```と同じ方法で k ごとに求めるので、
+/// メモリは O(n・q)。変数は1つ以上必要。
+pub fn multivariate_solutions<T>(fs: &[Polynomial<T>], c: T) -> MultivariateSolutions<'_, T>
+where
+    T: FiniteField + Zero + Identity + Add<Output = T> + Mul<Output = T> + MulAssign + AddAssign,
+    T: Copy + Eq,
+{
+    assert!(!fs.is_empty(), "変数は1つ以上必要です");
+    let mut suffix = vec![sum_value_counts::<T>(&[])];
+    for f in fs.iter().rev() {
+        let last = suffix.last().unwrap();
+        suffix.push(additive_convolution::<T>(last, &value_counts(f)));
+    }
+    suffix.reverse();
+    let mut solutions = MultivariateSolutions {
+        fs,
+        suffix,
+        fibers: Fibers::new(&fs[fs.len() - 1]),
+        stack: Vec::new(),
+        last: None,
+    };
+    if solutions.suffix[0][c.index() as usize] > 0 {
+        if fs.len() == 1 {
+            solutions.last = Some((c.index(), 0));
+        } else {
+            solutions.stack.push((0, c));
+        }
+    }
+    solutions
+}
+
+/// 方程式 f_1(x_1) + ... + f_n(x_n) = c の解を全探索で求める。計算量は O(q^n・n・deg)。
+pub fn solve_multivariate_brute_force<T>(fs: &[Polynomial<T>], c: T) -> SolutionSet<Point<T>>
+where
+    T: FiniteField + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Ord,
+{
+    let mut s: BTreeSet<Point<T>> = BTreeSet::new();
+    let mut indices = vec![0; fs.len()];
+    loop {
+        let mut sum = T::zero();
+        for (f, &i) in fs.iter().zip(&indices) {
+            sum += Polynomial::evaluate(f, T::element(i));
+        }
+        if sum == c {
+            s.insert(Point(indices.iter().map(|&i| T::element(i)).collect()));
+        }
+        // 最後の変数から繰り上げる
+        let mut k = fs.len();
+        loop {
+            if k == 0 {
+                return SolutionSet::new(s);
+            }
+            k -= 1;
+            indices[k] += 1;
+            if indices[k] < T::cardinality() {
+                break;
+            }
+            indices[k] = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::galois_field::{GaloisField, GfElem};
+    use crate::random::Random;
+    use crate::testing::random_polynomial;
+
+    /// 変数の個数と値 c をランダムに選び、個数・列挙・値の分布を全探索と比べる
+    fn check_against_brute_force<T>(random: &mut Random, max_variables: u64)
+    where
+        T: FiniteField + Zero + Identity + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+        T: MulAssign + AddAssign + Copy + Ord + fmt::Debug + fmt::Display,
+    {
+        let n = 1 + random.below(max_variables);
+        let fs: Vec<Polynomial<T>> = (0..n)
+            .map(|_| random_polynomial(random.below(5) as usize, random))
+            .collect();
+        let q = T::cardinality();
+        let counts = sum_value_counts(&fs);
+        assert_eq!(counts.iter().sum::<u64>(), q.pow(n as u32));
+        for i in 0..q {
+            let c = T::element(i);
+            let expected = solve_multivariate_brute_force(&fs, c);
+            let names: Vec<String> = fs.iter().map(|f| f.to_string()).collect();
+            assert_eq!(counts[i as usize], expected.size() as u64);
+            assert_eq!(count_multivariate_solutions(&fs, c), expected.size() as u64);
+            // 列挙は辞書式順なので、全探索の結果（小さい順）とそのまま一致する
+            let solutions: Vec<Point<T>> = multivariate_solutions(&fs, c).collect();
+            let expected: Vec<Point<T>> = expected.iter().cloned().collect();
+            assert_eq!(solutions, expected, "{} = {}", names.join(" + "), c);
+        }
+    }
+
+    #[test]
+    fn prime_field_matches_brute_force() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                check_against_brute_force::<DynModInt>(&mut random, 4);
+            }
+        }
+    }
+
+    #[test]
+    fn extension_field_matches_brute_force() {
+        let mut random = Random::default();
+        for &(p, k) in &[(2, 2), (3, 2)] {
+            let _field = GaloisField::with_degree(p, k).unwrap();
+            for _ in 0..10 {
+                check_against_brute_force::<GfElem>(&mut random, 3);
+            }
+        }
+    }
+
+    #[test]
+    fn sum_of_three_squares() {
+        // x^2 + y^2 + z^2 = c の解の個数は奇素数 p で p^2 + χ(-c)p（c ≠ 0）、c = 0 なら p^2
+        for &p in &[3u64, 5, 7, 11, 13] {
+            let _context = DynModContext::new(p);
+            let square =
+                Polynomial::new(&[DynModInt::zero(), DynModInt::zero(), DynModInt::identity()]);
+            let fs = vec![square.clone(), square.clone(), square];
+            let counts = sum_value_counts(&fs);
+            assert_eq!(counts[0], p * p);
+            for c in 1..p {
+                let minus_c = DynModInt::zero() - DynModInt::new(c);
+                let is_square = (1..p).any(|x| DynModInt::new(x) * DynModInt::new(x) == minus_c);
+                let expected = if is_square { p * p + p } else { p * p - p };
+                assert_eq!(counts[c as usize], expected, "c = {} mod {}", c, p);
+            }
+        }
+    }
+
+    #[test]
+    fn single_variable_is_fiber() {
+        let _context = DynModContext::new(7);
+        // x^3 = 1 の解は 1, 2, 4
+        let fs = vec![Polynomial::new(&[
+            DynModInt::zero(),
+            DynModInt::zero(),
+            DynModInt::zero(),
+            DynModInt::identity(),
+        ])];
+        let solutions: Vec<Point<DynModInt>> =
+            multivariate_solutions(&fs, DynModInt::identity()).collect();
+        let expected: Vec<Point<DynModInt>> = [1, 2, 4]
+            .iter()
+            .map(|&x| Point(vec![DynModInt::new(x)]))
+            .collect();
+        assert_eq!(solutions, expected);
+        assert_eq!(count_multivariate_solutions(&fs, DynModInt::new(3)), 0);
+        assert_eq!(multivariate_solutions(&fs, DynModInt::new(3)).next(), None);
+        assert_eq!(
+            sum_value_counts::<DynModInt>(&[]),
+            vec![1, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            Point(vec![DynModInt::new(1), DynModInt::new(2)]).to_string(),
+            "(1, 2)"
+        );
+    }
+}
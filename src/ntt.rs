@@ -0,0 +1,271 @@
+use crate::convolution::{karatsuba, Convolution};
+use crate::dyn_modint::DynModInt;
+use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
+use crate::modint::ModInt;
+use crate::montgomery::MontgomeryModInt;
+
+/// NTT に使う素数。いずれも 2^23 の倍数 + 1 で、原始根は 3。
+const P1: u64 = 998_244_353;
+const P2: u64 = 167_772_161;
+const P3: u64 = 469_762_049;
+
+/// NTT を使う列の長さの下限（短い方の長さ）
+const NTT_THRESHOLD: usize = 64;
+
+/// NTT で扱える列の長さの上限
+const NTT_MAX_LENGTH: usize = 1 << 23;
+
+/// 数論変換。```a.len()```は```P - 1```を割り切る2のべきとする。
+fn ntt<const P: u64>(a: &mut [MontgomeryModInt<P>], invert: bool) {
+    let n = a.len();
+    // ビット反転の順に並べ替える
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let root = MontgomeryModInt::<P>::new(3);
+    let mut len = 2;
+    while len <= n {
+        let mut w = root.modpow((P - 1) / len as u64);
+        if invert {
+            w = w.inverse().unwrap();
+        }
+        for chunk in a.chunks_mut(len) {
+            let mut wn = MontgomeryModInt::<P>::identity();
+            for k in 0..len / 2 {
+                let u = chunk[k];
+                let v = chunk[k + len / 2] * wn;
+                chunk[k] = u + v;
+                chunk[k + len / 2] = u - v;
+                wn *= w;
+            }
+        }
+        len <<= 1;
+    }
+    if invert {
+        let n_inv = MontgomeryModInt::<P>::new(n as u64).inverse().unwrap();
+        for x in a.iter_mut() {
+            *x *= n_inv;
+        }
+    }
+}
+
+/// 素数```P```を法とした畳み込み
+fn convolve_prime<const P: u64>(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let mut fa = vec![MontgomeryModInt::<P>::zero(); n];
+    let mut fb = vec![MontgomeryModInt::<P>::zero(); n];
+    for (x, &y) in fa.iter_mut().zip(a) {
+        *x = MontgomeryModInt::new(y);
+    }
+    for (x, &y) in fb.iter_mut().zip(b) {
+        *x = MontgomeryModInt::new(y);
+    }
+    ntt(&mut fa, false);
+    ntt(&mut fb, false);
+    for (x, &y) in fa.iter_mut().zip(fb.iter()) {
+        *x *= y;
+    }
+    ntt(&mut fa, true);
+    fa[..len].iter().map(|x| x.to_int()).collect()
+}
+
+/// 3つの NTT 素数での畳み込みの結果を中国剰余定理（Garner のアルゴリズム）でまとめ、
+/// ```m```を法とした畳み込みを返す。```can_use_ntt```が```true```の場合に限る。
+pub fn convolve_mod(a: &[u64], b: &[u64], m: u64) -> Vec<u64> {
+    // 法が NTT 素数そのものなら1回で済む
+    if m == P1 {
+        return convolve_prime::<P1>(a, b);
+    }
+    let c1 = convolve_prime::<P1>(a, b);
+    let c2 = convolve_prime::<P2>(a, b);
+    let c3 = convolve_prime::<P3>(a, b);
+    let p1_inv = ModInt::<P2>::new(P1).inverse().unwrap();
+    let p12_inv = (ModInt::<P3>::new(P1) * ModInt::<P3>::new(P2))
+        .inverse()
+        .unwrap();
+    let p12 = P1 as u128 * P2 as u128;
+    c1.iter()
+        .zip(c2.iter())
+        .zip(c3.iter())
+        .map(|((&r1, &r2), &r3)| {
+            let t2 = ((ModInt::<P2>::new(r2) - ModInt::<P2>::new(r1)) * p1_inv).to_int();
+            let x12 = r1 as u128 + P1 as u128 * t2 as u128;
+            let t3 = ((ModInt::<P3>::new(r3) - ModInt::<P3>::new((x12 % P3 as u128) as u64))
+                * p12_inv)
+                .to_int();
+            // x = x12 + p12 * t3 は P1 P2 P3 未満なので u128 に収まる
+            ((x12 + p12 * t3 as u128) % m as u128) as u64
+        })
+        .collect()
+}
+
+/// 法```m```の元からなる長さ```n```と```k```の列の畳み込みに NTT を使えるかどうか。
+///
+/// 真の値（整数としての畳み込み）が```P1 P2 P3```未満であれば中国剰余定理で復元できる。
+/// 短い列では Karatsuba 法の方が速いので使わない。
+pub fn can_use_ntt(n: usize, k: usize, m: u64) -> bool {
+    let shorter = n.min(k) as u128;
+    let bound = P1 as u128 * P2 as u128 * P3 as u128;
+    n.min(k) >= NTT_THRESHOLD
+        && n + k - 1 <= NTT_MAX_LENGTH
+        && (m as u128 - 1)
+            .checked_mul(m as u128 - 1)
+            .and_then(|x| x.checked_mul(shorter))
+            .is_some_and(|x| x < bound)
+}
+
+impl<const MOD: u64> Convolution for ModInt<MOD> {
+    fn convolve(a: &[Self], b: &[Self]) -> Vec<Self> {
+        if !can_use_ntt(a.len(), b.len(), MOD) {
+            return karatsuba(a, b);
+        }
+        let a: Vec<u64> = a.iter().map(|x| x.to_int()).collect();
+        let b: Vec<u64> = b.iter().map(|x| x.to_int()).collect();
+        convolve_mod(&a, &b, MOD)
+            .into_iter()
+            .map(ModInt::new)
+            .collect()
+    }
+}
+
+impl<const MOD: u64> Convolution for MontgomeryModInt<MOD> {
+    fn convolve(a: &[Self], b: &[Self]) -> Vec<Self> {
+        if !can_use_ntt(a.len(), b.len(), MOD) {
+            return karatsuba(a, b);
+        }
+        let a: Vec<u64> = a.iter().map(|x| x.to_int()).collect();
+        let b: Vec<u64> = b.iter().map(|x| x.to_int()).collect();
+        convolve_mod(&a, &b, MOD)
+            .into_iter()
+            .map(MontgomeryModInt::new)
+            .collect()
+    }
+}
+
+impl Convolution for DynModInt {
+    fn convolve(a: &[Self], b: &[Self]) -> Vec<Self> {
+        let m = DynModInt::modulus();
+        if !can_use_ntt(a.len(), b.len(), m) {
+            return karatsuba(a, b);
+        }
+        let a: Vec<u64> = a.iter().map(|x| x.to_int()).collect();
+        let b: Vec<u64> = b.iter().map(|x| x.to_int()).collect();
+        convolve_mod(&a, &b, m)
+            .into_iter()
+            .map(DynModInt::new)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convolution::schoolbook;
+    use crate::dyn_modint::DynModContext;
+    use crate::modint::BIG;
+    use crate::random::Random;
+
+    /// 整数としての畳み込みを```m```で割った余り
+    fn convolve_u128(a: &[u64], b: &[u64], m: u64) -> Vec<u64> {
+        let mut c = vec![0u128; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                c[i + j] = (c[i + j] + x as u128 * y as u128) % m as u128;
+            }
+        }
+        c.into_iter().map(|x| x as u64).collect()
+    }
+
+    #[test]
+    fn ntt_prime_modulus_matches_schoolbook() {
+        let mut random = Random::default();
+        for &(n, k) in &[(NTT_THRESHOLD, NTT_THRESHOLD), (100, 300), (1000, 513)] {
+            let a: Vec<ModInt<P1>> = (0..n).map(|_| ModInt::new(random.next_u64())).collect();
+            let b: Vec<ModInt<P1>> = (0..k).map(|_| ModInt::new(random.next_u64())).collect();
+            assert!(can_use_ntt(n, k, P1));
+            assert_eq!(
+                ModInt::convolve(&a, &b),
+                schoolbook(&a, &b),
+                "n = {}, k = {}",
+                n,
+                k
+            );
+        }
+    }
+
+    #[test]
+    fn garner_matches_schoolbook() {
+        // (m - 1)^2 × 長さ が P1 P2 P3 に近くなる法で、3つの素数の結果を復元する
+        let mut random = Random::default();
+        for &m in &[1_000_000_000_039, 1_000_000_007, 1 << 40] {
+            let _context = DynModContext::new(m);
+            let (n, k) = (NTT_THRESHOLD, 200);
+            assert!(can_use_ntt(n, k, m));
+            let a: Vec<DynModInt> = (0..n).map(|_| DynModInt::new(random.below(m))).collect();
+            let b: Vec<DynModInt> = (0..k).map(|_| DynModInt::new(random.below(m))).collect();
+            assert_eq!(DynModInt::convolve(&a, &b), schoolbook(&a, &b), "mod {}", m);
+            // 最大の値どうしでも復元できる
+            let a = vec![DynModInt::new(m - 1); n];
+            let b = vec![DynModInt::new(m - 1); k];
+            assert_eq!(DynModInt::convolve(&a, &b), schoolbook(&a, &b), "mod {}", m);
+        }
+    }
+
+    #[test]
+    fn garner_with_modulus_near_u64_max() {
+        // 値が小さければ、法が u64::MAX に近くても真の値を復元して簡約できる
+        let mut random = Random::default();
+        for &m in &[u64::MAX, BIG] {
+            let a: Vec<u64> = (0..300).map(|_| random.below(1 << 30)).collect();
+            let b: Vec<u64> = (0..500).map(|_| random.below(1 << 30)).collect();
+            assert_eq!(
+                convolve_mod(&a, &b, m),
+                convolve_u128(&a, &b, m),
+                "mod {}",
+                m
+            );
+        }
+        let a = vec![P1 - 1; 100];
+        let b = vec![P1 - 1; 100];
+        assert_eq!(convolve_mod(&a, &b, P1), convolve_u128(&a, &b, P1));
+    }
+
+    #[test]
+    fn can_use_ntt_bounds() {
+        assert!(!can_use_ntt(NTT_THRESHOLD - 1, 1000, P1));
+        assert!(can_use_ntt(NTT_THRESHOLD, 1000, P1));
+        assert!(!can_use_ntt(NTT_MAX_LENGTH, NTT_MAX_LENGTH, P1));
+        // (m - 1)^2 × 長さ が P1 P2 P3 以上なら使えない
+        assert!(!can_use_ntt(1000, 1000, BIG));
+        assert!(!can_use_ntt(1000, 1000, u64::MAX));
+        assert!(!can_use_ntt(1 << 20, 1 << 20, 1_000_000_000_039));
+    }
+
+    #[test]
+    fn large_modulus_falls_back_to_karatsuba() {
+        let mut random = Random::default();
+        let a: Vec<ModInt<BIG>> = (0..300).map(|_| ModInt::new(random.next_u64())).collect();
+        let b: Vec<ModInt<BIG>> = (0..200).map(|_| ModInt::new(random.next_u64())).collect();
+        assert_eq!(ModInt::convolve(&a, &b), schoolbook(&a, &b));
+        let a: Vec<MontgomeryModInt<BIG>> = a
+            .iter()
+            .map(|x| MontgomeryModInt::new(x.to_int()))
+            .collect();
+        let b: Vec<MontgomeryModInt<BIG>> = b
+            .iter()
+            .map(|x| MontgomeryModInt::new(x.to_int()))
+            .collect();
+        assert_eq!(MontgomeryModInt::convolve(&a, &b), schoolbook(&a, &b));
+    }
+}
@@ -0,0 +1,415 @@
+use crate::bivariate::BivariatePolynomial;
+use crate::identities::{Identity, Zero};
+use crate::polynomial::Polynomial;
+use std::fmt;
+use std::ops::{Add, Mul, Neg};
+
+/// 指数の上限。これより大きい指数は誤りとして扱う。
+const MAX_EXPONENT: usize = 1 << 20;
+
+/// 式の構文エラー。```position```は何文字目か（0始まり）。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(position: usize, message: &str) -> Self {
+        ParseError {
+            position,
+            message: message.to_string(),
+        }
+    }
+
+    /// 入力された式とエラー箇所を示す```^```を2行で返す。
+    pub fn show_position(&self, input: &str) -> String {
+        let mut s = String::from(input);
+        s.push('\n');
+        for c in input.chars().take(self.position) {
+            // 全角文字は幅2として数える
+            s.push_str(if c.is_ascii() { " " } else { "  " });
+        }
+        s.push('^');
+        s
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}文字目: {}", self.position + 1, self.message)
+    }
+}
+
+/// 係数と、x, y の指数の組
+struct Term<T> {
+    coefficient: T,
+    exponents: (usize, usize),
+    /// 項が始まる位置（エラー表示用）
+    position: usize,
+}
+
+/// 方程式の左辺と右辺の項の列
+type Sides<T> = (Vec<Term<T>>, Vec<Term<T>>);
+
+/// ```f(x) = g(y)```の形の方程式を読み、両辺の多項式```(f, g)```を返す。
+///
+/// 係数と変数の間の```*```は省略でき、```^```でべき乗を表す。
+/// 負の係数や大きな係数は係数環で簡約される。
+/// ```y```の項が左辺にある場合や```x```の項が右辺にある場合は移項して扱い、
+/// 定数項はそれぞれの辺に残す。
+/// ```Polynomial::print_f_of_x```と```Polynomial::print_f_of_y```の出力を```=```で
+/// つないだものは同じ多項式に戻る。
+pub fn parse_equation<T>(input: &str) -> Result<(Polynomial<T>, Polynomial<T>), ParseError>
+where
+    T: Zero + Identity + Add<Output = T> + Mul<Output = T> + Neg<Output = T> + Copy + Eq,
+{
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        position: 0,
+    };
+    let mut f: Vec<T> = vec![T::zero()];
+    let mut g: Vec<T> = vec![T::zero()];
+
+    let (lhs, rhs) = parser.equation::<T>()?;
+    for term in lhs.iter().chain(rhs.iter()) {
+        if term.exponents.0 > 0 && term.exponents.1 > 0 {
+            return Err(ParseError::new(
+                term.position,
+                "x と y の積の項は f(x) = g(y) の形では扱えません",
+            ));
+        }
+    }
+    for term in lhs {
+        match term.exponents {
+            (0, e) if e > 0 => add_term(&mut g, e, -term.coefficient),
+            (e, _) => add_term(&mut f, e, term.coefficient),
+        }
+    }
+    for term in rhs {
+        match term.exponents {
+            (e, 0) if e > 0 => add_term(&mut f, e, -term.coefficient),
+            (_, e) => add_term(&mut g, e, term.coefficient),
+        }
+    }
+
+    Ok((Polynomial::new(&f), Polynomial::new(&g)))
+}
+
+/// ```F(x, y) = G(x, y)```の形の方程式を読み、2変数多項式 F - G を返す。
+///
+/// 書き方は```parse_equation```と同じで、```xy^2```のような x と y の積の項も使える。
+/// ```BivariatePolynomial```の表示に```= 0```をつなげたものは同じ多項式に戻る。
+pub fn parse_bivariate<T>(input: &str) -> Result<BivariatePolynomial<T>, ParseError>
+where
+    T: Zero + Identity + Add<Output = T> + Mul<Output = T> + Neg<Output = T> + Copy + Eq,
+{
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        position: 0,
+    };
+    let (lhs, rhs) = parser.equation::<T>()?;
+    let mut f = BivariatePolynomial::zero();
+    for term in lhs {
+        f.add_term(term.exponents, term.coefficient);
+    }
+    for term in rhs {
+        f.add_term(term.exponents, -term.coefficient);
+    }
+    Ok(f)
+}
+
+/// 係数の列の```e```次の項に```c```を足す
+fn add_term<T: Zero + Add<Output = T> + Copy>(v: &mut Vec<T>, e: usize, c: T) {
+    if v.len() <= e {
+        v.resize(e + 1, T::zero());
+    }
+    v[e] = v[e] + c;
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.position, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// ```=```でつながれた両辺を項の列として読む
+    fn equation<T>(&mut self) -> Result<Sides<T>, ParseError>
+    where
+        T: Zero + Identity + Add<Output = T> + Mul<Output = T> + Neg<Output = T> + Copy,
+    {
+        let lhs = self.side::<T>()?;
+        self.skip_whitespace();
+        match self.peek() {
+            Some('=') => self.position += 1,
+            Some(_) => return Err(self.error("'+', '-' または '=' が必要です")),
+            None => return Err(self.error("'=' がありません")),
+        }
+        let rhs = self.side::<T>()?;
+        self.skip_whitespace();
+        if self.peek().is_some() {
+            return Err(self.error("'+' または '-' が必要です"));
+        }
+        Ok((lhs, rhs))
+    }
+
+    /// 片方の辺を項の列として読む
+    fn side<T>(&mut self) -> Result<Vec<Term<T>>, ParseError>
+    where
+        T: Zero + Identity + Add<Output = T> + Mul<Output = T> + Neg<Output = T> + Copy,
+    {
+        let mut terms = Vec::new();
+        self.skip_whitespace();
+        let mut negative = self.sign();
+        loop {
+            let mut term = self.term::<T>()?;
+            if negative {
+                term.coefficient = -term.coefficient;
+            }
+            terms.push(term);
+            self.skip_whitespace();
+            match self.peek() {
+                Some('+') | Some('-') => negative = self.sign(),
+                _ => break,
+            }
+        }
+        Ok(terms)
+    }
+
+    /// 先頭の符号を読む。負なら```true```。
+    fn sign(&mut self) -> bool {
+        let mut negative = false;
+        while let Some(c) = self.peek() {
+            match c {
+                '+' => {}
+                '-' => negative = !negative,
+                _ => break,
+            }
+            self.position += 1;
+            self.skip_whitespace();
+        }
+        negative
+    }
+
+    /// 数と変数のべきの積を1つの項として読む
+    fn term<T>(&mut self) -> Result<Term<T>, ParseError>
+    where
+        T: Zero + Identity + Add<Output = T> + Mul<Output = T> + Copy,
+    {
+        self.skip_whitespace();
+        let mut term = Term {
+            coefficient: T::identity(),
+            exponents: (0, 0),
+            position: self.position,
+        };
+        let mut factors = 0;
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            match self.peek() {
+                Some(c) if c.is_ascii_digit() => {
+                    let n: T = to_element(&self.digits());
+                    term.coefficient = term.coefficient * n;
+                }
+                Some(c) if c == 'x' || c == 'y' => {
+                    self.position += 1;
+                    let e = self.exponent()?;
+                    let d = if c == 'x' {
+                        &mut term.exponents.0
+                    } else {
+                        &mut term.exponents.1
+                    };
+                    if *d + e > MAX_EXPONENT {
+                        return Err(ParseError::new(start, "指数が大きすぎます"));
+                    }
+                    *d += e;
+                }
+                _ if factors == 0 => return Err(self.error("数または変数 x, y が必要です")),
+                _ => {
+                    return Err(self.error("'*' の後には数または変数 x, y が必要です"));
+                }
+            }
+            factors += 1;
+            self.skip_whitespace();
+            match self.peek() {
+                Some('*') => self.position += 1,
+                Some(c) if c.is_ascii_digit() || c == 'x' || c == 'y' => {}
+                _ => break,
+            }
+        }
+        Ok(term)
+    }
+
+    /// 10進数の数字の並びを読む
+    fn digits(&mut self) -> String {
+        let mut s = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() {
+                s.push(c);
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        s
+    }
+
+    /// 変数の直後の```^```と指数を読む。無ければ1乗。
+    fn exponent(&mut self) -> Result<usize, ParseError> {
+        self.skip_whitespace();
+        if self.peek() != Some('^') {
+            return Ok(1);
+        }
+        self.position += 1;
+        self.skip_whitespace();
+        let start = self.position;
+        match self.peek() {
+            Some(c) if c.is_ascii_digit() => {}
+            _ => return Err(self.error("'^' の後には指数（0以上の整数）が必要です")),
+        }
+        match self.digits().parse::<usize>() {
+            Ok(e) if e <= MAX_EXPONENT => Ok(e),
+            _ => Err(ParseError::new(start, "指数が大きすぎます")),
+        }
+    }
+}
+
+/// 10進数の数字の並びを係数環の元にする。桁が多くても係数環の中で計算するので溢れない。
+fn to_element<T>(digits: &str) -> T
+where
+    T: Zero + Identity + Add<Output = T> + Mul<Output = T> + Copy,
+{
+    let one = T::identity();
+    let mut ten = T::zero();
+    for _ in 0..10 {
+        ten = ten + one;
+    }
+    let mut value = T::zero();
+    for c in digits.chars() {
+        let mut digit = T::zero();
+        for _ in 0..c.to_digit(10).unwrap_or(0) {
+            digit = digit + one;
+        }
+        value = value * ten + digit;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::random::Random;
+    use crate::testing::polynomial;
+
+    fn error_of(input: &str) -> ParseError {
+        parse_equation::<DynModInt>(input).unwrap_err()
+    }
+
+    #[test]
+    fn parse_simple_equation() {
+        let _context = DynModContext::new(7);
+        let (f, g) = parse_equation::<DynModInt>("x^3 + 2x + 1 = y^2 + 4").unwrap();
+        assert_eq!(f, polynomial(&[1, 2, 0, 1]));
+        assert_eq!(g, polynomial(&[4, 0, 1]));
+        // 移項、符号、省略された```*```、大きな係数の簡約
+        let (f, g) = parse_equation::<DynModInt>("-y^2 + 3 * x * x = -1 - 10x + 15").unwrap();
+        assert_eq!(f, polynomial(&[0, 3, 3]));
+        assert_eq!(g, polynomial(&[0, 0, 1]));
+        let (f, g) = parse_equation::<DynModInt>("x - - x = 2y^0").unwrap();
+        assert_eq!((f, g), (polynomial(&[0, 2]), polynomial(&[2])));
+    }
+
+    #[test]
+    fn printed_polynomials_parse_back() {
+        let mut random = Random::default();
+        for &p in &[2, 7, 101, 1_000_000_007] {
+            let _context = DynModContext::new(p);
+            for _ in 0..100 {
+                let coefficients = |random: &mut Random| -> Vec<u64> {
+                    let n = random.below(8) as usize;
+                    // 0 と 1 の係数も多めに出す
+                    (0..=n)
+                        .map(|_| random.below(p.min(4)).max(random.below(p)))
+                        .collect()
+                };
+                let f = polynomial::<DynModInt>(&coefficients(&mut random));
+                let g = polynomial(&coefficients(&mut random));
+                let input = format!("{} = {}", f.print_f_of_x(), g.print_f_of_y());
+                assert_eq!(parse_equation(&input), Ok((f, g)), "{}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn error_positions() {
+        let _context = DynModContext::new(7);
+        let cases = [
+            ("x^", 2),
+            ("2*", 2),
+            ("x = ", 4),
+            ("xy = 1", 0),
+            ("1 + 2xy^2 = y", 4),
+            ("x + 1", 5),
+            ("x + 1 = y z", 10),
+            ("x = y )", 6),
+            ("x ^ a = y", 4),
+        ];
+        for &(input, position) in &cases {
+            assert_eq!(error_of(input).position, position, "{}", input);
+        }
+        assert_eq!(
+            error_of("2*").message,
+            "'*' の後には数または変数 x, y が必要です"
+        );
+        assert_eq!(error_of("x + 1").message, "'=' がありません");
+    }
+
+    #[test]
+    fn oversized_exponent() {
+        let _context = DynModContext::new(7);
+        let e = error_of("x^99999999999999999999999 = y");
+        assert_eq!((e.position, e.message.as_str()), (2, "指数が大きすぎます"));
+        let e = error_of(&format!("x^{} = y", MAX_EXPONENT + 1));
+        assert_eq!(e.position, 2);
+        // 指数の合計が上限を超える場合は、超えた変数の位置
+        let e = error_of(&format!("x^{} * x = y", MAX_EXPONENT));
+        assert_eq!((e.position, e.message.as_str()), (12, "指数が大きすぎます"));
+        assert!(parse_equation::<DynModInt>(&format!("x^{} = y", MAX_EXPONENT)).is_ok());
+    }
+
+    #[test]
+    fn show_position_and_display() {
+        let _context = DynModContext::new(7);
+        // 指数が無い場合は、空白を飛ばした次の文字を指す
+        let input = "x^ = y";
+        let e = error_of(input);
+        assert_eq!(e.show_position(input), "x^ = y\n   ^");
+        assert_eq!(
+            e.to_string(),
+            "4文字目: '^' の後には指数（0以上の整数）が必要です"
+        );
+        // 全角文字は幅2として数える
+        let e = ParseError::new(2, "");
+        assert_eq!(e.show_position("ｘ+ y"), "ｘ+ y\n   ^");
+    }
+}
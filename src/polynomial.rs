@@ -1,6 +1,8 @@
+use crate::convolution::Convolution;
 use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
 use std::fmt;
-use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
 
 /// （1変数の）多項式
 ///
@@ -18,6 +20,11 @@ impl<T> Polynomial<T> {
         self.degree
     }
 
+    /// 係数の列。i番目がi次の係数。
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
     /// 係数環の元から定数（0次多項式）を作る。
     pub fn new_constant(t: T) -> Self {
         Self {
@@ -36,14 +43,26 @@ impl<T: Zero + Eq> Polynomial<T> {
             Some(self.degree)
         }
     }
+
+    /// 零多項式かどうか
+    pub fn is_zero(&self) -> bool {
+        self.strict_deg().is_none()
+    }
+}
+
+impl<T: Copy> Polynomial<T> {
+    /// 最高次係数
+    pub fn leading_coefficient(&self) -> T {
+        self.coefficients[self.degree]
+    }
 }
 
 impl<T: Zero + Eq + Copy> Polynomial<T> {
     /// ```T```型のデータからなるベクトルを受け取り、それを係数としてもつ多項式を作る。
     /// 最高次係数がnon-zeroになるようにするのでTには```Zero```や```Eq```を要求。
-    pub fn new(v: &Vec<T>) -> Self {
+    pub fn new(v: &[T]) -> Self {
         let mut f: Vec<T> = Vec::new();
-        if v.len() == 0 {
+        if v.is_empty() {
             f.push(T::zero());
             Self {
                 coefficients: f,
@@ -52,7 +71,7 @@ impl<T: Zero + Eq + Copy> Polynomial<T> {
         } else {
             f.push(v[0]);
             for &t in &v[1..] {
-                f.push(t.clone());
+                f.push(t);
             }
             while f.len() > 1 {
                 if let Some(&t) = f.last() {
@@ -101,48 +120,13 @@ impl<T: Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq> P
 /// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
 impl<T: fmt::Display + Zero + Identity + Eq> fmt::Display for Polynomial<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut s: String = String::new();
-        let mut flag = false;
-        if self.degree == 0 || self.coefficients[0] != T::zero() {
-            s.push_str(&self.coefficients[0].to_string())
-        } else {
-            flag = true;
-        }
-        if self.degree > 0 && self.coefficients[1] != T::zero() {
-            if !flag {
-                s.push_str(&" + ");
-            } else {
-                flag = false;
-            }
-            if self.coefficients[1] != T::identity() {
-                s.push_str(&self.coefficients[1].to_string());
-            }
-            s.push_str(&"x");
-        }
-        if self.degree > 1 {
-            for i in 2..=self.degree {
-                if self.coefficients[i] == T::zero() {
-                    continue;
-                }
-                if !flag {
-                    s.push_str(&" + ");
-                } else {
-                    flag = false;
-                }
-                if self.coefficients[i] != T::identity() {
-                    s.push_str(&self.coefficients[i].to_string());
-                }
-                s.push_str(&"x^");
-                s.push_str(&i.to_string());
-            }
-        }
-        write!(f, "{}", s)
+        write!(f, "{}", self.print_with_variable("x"))
     }
 }
 
 impl<T: fmt::Display + Zero + Identity + Eq> Polynomial<T> {
-    /// xの多項式としての表示
-    pub fn print_f_of_x(&self) -> String {
+    /// 変数名を```variable```として表示
+    pub fn print_with_variable(&self, variable: &str) -> String {
         let mut s: String = String::new();
         let mut flag = false;
         if self.degree == 0 || self.coefficients[0] != T::zero() {
@@ -150,75 +134,43 @@ impl<T: fmt::Display + Zero + Identity + Eq> Polynomial<T> {
         } else {
             flag = true;
         }
-        if self.degree > 0 && self.coefficients[1] != T::zero() {
+        for i in 1..=self.degree {
+            if self.coefficients[i] == T::zero() {
+                continue;
+            }
             if !flag {
-                s.push_str(&" + ");
+                s.push_str(" + ");
             } else {
                 flag = false;
             }
-            if self.coefficients[1] != T::identity() {
-                s.push_str(&self.coefficients[1].to_string());
-            }
-            s.push_str(&"x");
-        }
-        if self.degree > 1 {
-            for i in 2..=self.degree {
-                if self.coefficients[i] == T::zero() {
-                    continue;
-                }
-                if !flag {
-                    s.push_str(&" + ");
+            if self.coefficients[i] != T::identity() {
+                // 係数自体が和で表示される場合（拡大体の元など）は括弧でくくる
+                let c = self.coefficients[i].to_string();
+                if c.contains(' ') {
+                    s.push('(');
+                    s.push_str(&c);
+                    s.push(')');
                 } else {
-                    flag = false;
+                    s.push_str(&c);
                 }
-                if self.coefficients[i] != T::identity() {
-                    s.push_str(&self.coefficients[i].to_string());
-                }
-                s.push_str(&"x^");
+            }
+            s.push_str(variable);
+            if i > 1 {
+                s.push('^');
                 s.push_str(&i.to_string());
             }
         }
         s
     }
 
+    /// xの多項式としての表示
+    pub fn print_f_of_x(&self) -> String {
+        self.print_with_variable("x")
+    }
+
     /// yの多項式としての表示
     pub fn print_f_of_y(&self) -> String {
-        let mut s: String = String::new();
-        let mut flag = false;
-        if self.degree == 0 || self.coefficients[0] != T::zero() {
-            s.push_str(&self.coefficients[0].to_string())
-        } else {
-            flag = true;
-        }
-        if self.degree > 0 && self.coefficients[1] != T::zero() {
-            if !flag {
-                s.push_str(&" + ");
-            } else {
-                flag = false;
-            }
-            if self.coefficients[1] != T::identity() {
-                s.push_str(&self.coefficients[1].to_string());
-            }
-            s.push_str(&"y");
-        }
-        if self.degree > 1 {
-            for i in 2..=self.degree {
-                if self.coefficients[i] == T::zero() {
-                    continue;
-                }
-                if !flag {
-                    s.push_str(&" + ");
-                } else {
-                    flag = false;
-                }
-                if self.coefficients[i] != T::identity() {
-                    s.push_str(&self.coefficients[i].to_string());
-                }
-                s.push_str(&"y^");
-                s.push_str(&i.to_string());
-            }
-        }
-        s
+        self.print_with_variable("y")
     }
 }
 
@@ -335,34 +287,19 @@ impl<T: Copy + Sub<Output = T> + Zero + Eq> SubAssign for Polynomial<T> {
 }
 
 /// 掛け算の実装
-impl<T: Copy + Add<Output = T> + AddAssign<T> + Mul<Output = T> + Zero + Eq> Mul for Polynomial<T> {
+///
+/// 積の次数は deg f + deg g。係数列の積は```Convolution```に任せるので、
+/// 次数に応じて筆算・Karatsuba 法・数論変換が自動で選ばれる。
+impl<T: Convolution + Eq> Mul for Polynomial<T> {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self {
-        let mut v: Vec<T> = vec![T::zero(); self.degree * rhs.degree + 1];
-        for i in 0..=(self.degree * rhs.degree) {
-            for j in 0..=i {
-                if i - j <= rhs.degree && j <= self.degree {
-                    v[i] += self.coefficients[j] * rhs.coefficients[i - j];
-                }
-            }
-        }
-        Polynomial::new(&v)
+        Polynomial::new(&T::convolve(&self.coefficients, &rhs.coefficients))
     }
 }
 
-impl<T: Copy + Add<Output = T> + AddAssign<T> + Mul<Output = T> + Zero + Eq> MulAssign
-    for Polynomial<T>
-{
+impl<T: Convolution + Eq> MulAssign for Polynomial<T> {
     fn mul_assign(&mut self, rhs: Self) {
-        let mut v: Vec<T> = vec![T::zero(); self.degree * rhs.degree + 1];
-        for i in 0..=(self.degree * rhs.degree) {
-            for j in 0..=i {
-                if i - j <= rhs.degree && j <= self.degree {
-                    v[i] += self.coefficients[j] * rhs.coefficients[i - j];
-                }
-            }
-        }
-        *self = Polynomial::new(&v)
+        *self = Polynomial::new(&T::convolve(&self.coefficients, &rhs.coefficients))
     }
 }
 
@@ -378,6 +315,177 @@ impl<T: Zero + Eq + Copy + Neg<Output = T>> Neg for Polynomial<T> {
     }
 }
 
+/// 多項式の割り算の誤り
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DivisionError {
+    /// 零多項式で割ろうとした
+    DivisionByZero,
+    /// 割る多項式の最高次係数が可逆でない
+    NonInvertibleLeadingCoefficient,
+    /// 割り切れない
+    NotDivisible,
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl fmt::Display for DivisionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DivisionError::DivisionByZero => write!(f, "零多項式で割ろうとしました"),
+            DivisionError::NonInvertibleLeadingCoefficient => {
+                write!(f, "割る多項式の最高次係数が可逆ではありません")
+            }
+            DivisionError::NotDivisible => write!(f, "割り切れません"),
+        }
+    }
+}
+
+impl std::error::Error for DivisionError {}
+
+/// 商と割る式の次数がともにこれ以上なら、ニュートン法で逆数を求めて割り算する
+const FAST_DIVISION_THRESHOLD: usize = 128;
+
+impl<T: Convolution + Identity + Inverse + Eq> Polynomial<T> {
+    /// 割り算。```self = q * rhs + r```かつ```deg r < deg rhs```となる```(q, r)```を返す。
+    ///
+    /// 割る多項式の最高次係数は可逆である必要がある（係数環が体なら零でなければよい）。
+    /// 次数が大きい場合は、割る多項式を反転した形式的べき級数の逆元を
+    /// ニュートン法で求めて O(n log n) 回の掛け算に帰着する。
+    pub fn div_rem(&self, rhs: &Self) -> Result<(Self, Self), DivisionError> {
+        if rhs.is_zero() {
+            return Err(DivisionError::DivisionByZero);
+        }
+        let lc_inv = match rhs.leading_coefficient().inverse() {
+            Some(c) => c,
+            None => return Err(DivisionError::NonInvertibleLeadingCoefficient),
+        };
+        if self.is_zero() || self.degree < rhs.degree {
+            return Ok((Self::zero(), self.clone()));
+        }
+        let n = self.degree;
+        let m = rhs.degree;
+        let k = n - m + 1;
+
+        if m >= FAST_DIVISION_THRESHOLD && k >= FAST_DIVISION_THRESHOLD {
+            // 反転した多項式で考えると rev(self) = rev(q) rev(rhs) (mod x^k)
+            let a: Vec<T> = self.coefficients.iter().rev().take(k).copied().collect();
+            let b: Vec<T> = rhs.coefficients.iter().rev().copied().collect();
+            let mut q = T::convolve(&a, &series_inverse(&b, lc_inv, k));
+            q.truncate(k);
+            q.reverse();
+            let q = Polynomial::new(&q);
+            let r = self.clone() - q.clone() * rhs.clone();
+            return Ok((q, r));
+        }
+
+        // 筆算
+        let mut r = self.coefficients.clone();
+        let mut q = vec![T::zero(); k];
+        for i in (m..=n).rev() {
+            let c = r[i] * lc_inv;
+            q[i - m] = c;
+            for j in 0..=m {
+                r[i - m + j] = r[i - m + j] - c * rhs.coefficients[j];
+            }
+        }
+        r.truncate(m.max(1));
+        Ok((Polynomial::new(&q), Polynomial::new(&r)))
+    }
+
+    /// 割り切れる場合に商を返す。割り切れなければ```DivisionError::NotDivisible```。
+    pub fn exact_div(&self, rhs: &Self) -> Result<Self, DivisionError> {
+        let (q, r) = self.div_rem(rhs)?;
+        if r.is_zero() {
+            Ok(q)
+        } else {
+            Err(DivisionError::NotDivisible)
+        }
+    }
+
+    /// ```rhs```で割り切れるかどうか。割れない場合（零多項式など）は```false```。
+    pub fn is_divisible_by(&self, rhs: &Self) -> bool {
+        self.exact_div(rhs).is_ok()
+    }
+
+    /// 相異なる```xs[i]```で値```values[i]```をとる、次数が```xs.len()```未満の多項式
+    /// （Lagrange 補間）。計算量は O(n^2)。```xs```に同じ元があるとパニックする。
+    pub fn interpolate(xs: &[T], values: &[T]) -> Self {
+        let n = xs.len();
+        // m(t) = Π (t - x_i)
+        let mut m = vec![T::identity()];
+        for &x in xs {
+            let mut next = vec![T::zero(); m.len() + 1];
+            for (k, &c) in m.iter().enumerate() {
+                next[k + 1] += c;
+                next[k] = next[k] - c * x;
+            }
+            m = next;
+        }
+        let mut ans = vec![T::zero(); n.max(1)];
+        let mut quotient = vec![T::zero(); n];
+        for (&x, &value) in xs.iter().zip(values) {
+            if value == T::zero() {
+                continue;
+            }
+            // m(t) / (t - x) を組立除法で求め、x での値も同時に求める
+            let mut carry = T::zero();
+            let mut denominator = T::zero();
+            for k in (0..n).rev() {
+                carry = m[k + 1] + carry * x;
+                quotient[k] = carry;
+                denominator = denominator * x + carry;
+            }
+            let weight = value * denominator.inverse().expect("補間点が重複しています");
+            for k in 0..n {
+                ans[k] += weight * quotient[k];
+            }
+        }
+        Polynomial::new(&ans)
+    }
+}
+
+/// 形式的べき級数```b```（```b[0]```の逆元が```b0_inv```）の逆元を```x^k```の項まで求める。
+fn series_inverse<T: Convolution + Identity>(b: &[T], b0_inv: T, k: usize) -> Vec<T> {
+    let two = T::identity() + T::identity();
+    let mut g = vec![b0_inv];
+    let mut n = 1;
+    while n < k {
+        n *= 2;
+        // g <- g (2 - b g) (mod x^n)
+        let mut e = T::convolve(&b[..n.min(b.len())], &g);
+        e.truncate(n);
+        for c in e.iter_mut() {
+            *c = T::zero() - *c;
+        }
+        e[0] += two;
+        g = T::convolve(&g, &e);
+        g.truncate(n);
+    }
+    g.truncate(k);
+    g
+}
+
+/// 割り算（商）の実装。零多項式で割るとパニックする。
+impl<T: Convolution + Identity + Inverse + Eq> Div for Polynomial<T> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        match self.div_rem(&rhs) {
+            Ok((q, _)) => q,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+/// 割り算の余りの実装。零多項式で割るとパニックする。
+impl<T: Convolution + Identity + Inverse + Eq> Rem for Polynomial<T> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self {
+        match self.div_rem(&rhs) {
+            Ok((_, r)) => r,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
 /// ```Zero```の実装
 impl<T: Zero + Copy> Zero for Polynomial<T> {
     fn zero() -> Self {
@@ -397,3 +505,108 @@ impl<T: Identity + Copy> Identity for Polynomial<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convolution::schoolbook;
+    use crate::modint::{ModInt, BIG};
+    use crate::random::Random;
+    use crate::testing::random_polynomial;
+
+    /// ```a = q b + r```かつ```deg r < deg b```を確かめる
+    fn check_div_rem<const MOD: u64>(a: &Polynomial<ModInt<MOD>>, b: &Polynomial<ModInt<MOD>>) {
+        let (q, r) = a.div_rem(b).unwrap();
+        assert_eq!(
+            q.clone() * b.clone() + r.clone(),
+            *a,
+            "deg a = {}, deg b = {}",
+            a.deg(),
+            b.deg()
+        );
+        assert!(r.is_zero() || r.deg() < b.deg());
+    }
+
+    #[test]
+    fn product_degree() {
+        let mut random = Random::default();
+        for &(n, k) in &[(0, 0), (3, 5), (40, 40), (100, 70)] {
+            let f = random_polynomial::<ModInt<998_244_353>>(n, &mut random);
+            let g = random_polynomial::<ModInt<998_244_353>>(k, &mut random);
+            let h = f.clone() * g.clone();
+            assert_eq!(h.deg(), n + k);
+            assert_eq!(
+                h.coefficients(),
+                &schoolbook(f.coefficients(), g.coefficients())[..]
+            );
+        }
+        let f = random_polynomial::<ModInt<998_244_353>>(5, &mut random);
+        assert!((f * Polynomial::zero()).is_zero());
+    }
+
+    #[test]
+    fn newton_division() {
+        // 商と割る式の次数がともに FAST_DIVISION_THRESHOLD 以上だとニュートン法になる
+        let mut random = Random::default();
+        let t = FAST_DIVISION_THRESHOLD;
+        for &(n, m) in &[
+            (2 * t - 2, t - 1),
+            (2 * t - 1, t),
+            (2 * t + 5, t),
+            (1000, 300),
+            (3000, 2000),
+        ] {
+            let a = random_polynomial::<ModInt<998_244_353>>(n, &mut random);
+            let b = random_polynomial::<ModInt<998_244_353>>(m, &mut random);
+            check_div_rem(&a, &b);
+            // NTT を使わない法でも同じ
+            let a = random_polynomial::<ModInt<BIG>>(n, &mut random);
+            let b = random_polynomial::<ModInt<BIG>>(m, &mut random);
+            check_div_rem(&a, &b);
+        }
+    }
+
+    #[test]
+    fn newton_exact_division() {
+        // 割り切れる場合は商が元に戻る
+        let mut random = Random::default();
+        let q = random_polynomial::<ModInt<998_244_353>>(500, &mut random);
+        let b = random_polynomial::<ModInt<998_244_353>>(400, &mut random);
+        let a = q.clone() * b.clone();
+        assert_eq!(a.exact_div(&b), Ok(q));
+        let one = Polynomial::identity();
+        assert_eq!(
+            (a.clone() + one).exact_div(&b),
+            Err(DivisionError::NotDivisible)
+        );
+    }
+
+    #[test]
+    fn small_division() {
+        let mut random = Random::default();
+        for n in 0..20 {
+            for m in 0..20 {
+                let a = random_polynomial::<ModInt<7>>(n, &mut random);
+                let b = random_polynomial::<ModInt<7>>(m, &mut random);
+                if b.is_zero() {
+                    continue;
+                }
+                check_div_rem(&a, &b);
+            }
+        }
+    }
+
+    #[test]
+    fn division_errors() {
+        let a = Polynomial::new(&[ModInt::<6>::new(1), ModInt::new(1)]);
+        assert_eq!(
+            a.div_rem(&Polynomial::zero()),
+            Err(DivisionError::DivisionByZero)
+        );
+        let b = Polynomial::new(&[ModInt::<6>::new(1), ModInt::new(2)]);
+        assert_eq!(
+            a.div_rem(&b),
+            Err(DivisionError::NonInvertibleLeadingCoefficient)
+        );
+    }
+}
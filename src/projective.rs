@@ -0,0 +1,321 @@
+use crate::convolution::Convolution;
+use crate::finite_ring::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::polynomial::Polynomial;
+use std::fmt;
+use std::ops::{AddAssign, Mul, MulAssign};
+
+/// 射影平面 P^2 の点。アフィンの点 (x, y) は (x : y : 1) と同一視する。
+///
+/// 小さい順に並べるとアフィンの点が先、無限遠の点が後になる。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ProjectivePoint<T> {
+    /// アフィンの点 (x : y : 1)
+    Affine(T, T),
+    /// 無限遠の点 (x : y : 0)。最後の零でない座標を```1```に正規化する。
+    AtInfinity(T, T),
+}
+
+impl<T> ProjectivePoint<T> {
+    /// 無限遠の点かどうか
+    pub fn is_at_infinity(&self) -> bool {
+        matches!(self, ProjectivePoint::AtInfinity(_, _))
+    }
+}
+
+/// アフィンの点は```(x, y)```、無限遠の点は```(x : y : 0)```と表示する。
+impl<T: fmt::Display> fmt::Display for ProjectivePoint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProjectivePoint::Affine(x, y) => write!(f, "({}, {})", x, y),
+            ProjectivePoint::AtInfinity(x, y) => write!(f, "({} : {} : 0)", x, y),
+        }
+    }
+}
+
+/// 曲線 f(x) = g(y) の射影閉包 Z^D f(X/Z) = Z^D g(Y/Z)（D = max(deg f, deg g)）
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectiveClosure<T> {
+    f: Polynomial<T>,
+    g: Polynomial<T>,
+}
+
+impl<T> ProjectiveClosure<T>
+where
+    T: Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+{
+    /// コンストラクタ
+    pub fn new(f: &Polynomial<T>, g: &Polynomial<T>) -> Self {
+        ProjectiveClosure {
+            f: f.clone(),
+            g: g.clone(),
+        }
+    }
+
+    /// 斉次式としての次数 D
+    pub fn degree(&self) -> usize {
+        self.f.deg().max(self.g.deg())
+    }
+
+    /// 斉次化した方程式の両辺に (X, Y, Z) を代入した値
+    pub fn evaluate(&self, x: T, y: T, z: T) -> (T, T) {
+        let d = self.degree();
+        (
+            evaluate_homogeneous(&self.f, x, z, d),
+            evaluate_homogeneous(&self.g, y, z, d),
+        )
+    }
+
+    /// 点が曲線上にあるかどうか
+    pub fn contains(&self, point: &ProjectivePoint<T>) -> bool {
+        let (a, b) = match *point {
+            ProjectivePoint::Affine(x, y) => self.evaluate(x, y, T::identity()),
+            ProjectivePoint::AtInfinity(x, y) => self.evaluate(x, y, T::zero()),
+        };
+        a == b
+    }
+}
+
+impl<T: fmt::Display + Zero + Identity + Eq> ProjectiveClosure<T> {
+    /// 斉次化した方程式の表示（例: X^3 + XZ^2 + Z^3 = Y^2Z）
+    pub fn print(&self) -> String {
+        let d = self.f.deg().max(self.g.deg());
+        format!(
+            "{} = {}",
+            print_homogeneous(&self.f, "X", d),
+            print_homogeneous(&self.g, "Y", d)
+        )
+    }
+}
+
+impl<T> ProjectiveClosure<T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    /// 無限遠直線 Z = 0 上の点を小さい順に並べて返す。
+    ///
+    /// m = deg f, n = deg g とすると、m > n なら (0 : 1 : 0)、m < n なら (1 : 0 : 0)、
+    /// m = n なら a t^m = b（a, b は最高次係数）の根 t ごとに (t : 1 : 0) が無限遠の点になる。
+    /// f, g がともに定数の場合、方程式は Z を含まないので、f = g なら無限遠直線の q + 1 個の点すべて、
+    /// そうでなければ空になる。
+    pub fn points_at_infinity(&self) -> Vec<ProjectivePoint<T>> {
+        let (m, n) = (self.f.deg(), self.g.deg());
+        if m > n {
+            vec![ProjectivePoint::AtInfinity(T::zero(), T::identity())]
+        } else if m < n {
+            vec![ProjectivePoint::AtInfinity(T::identity(), T::zero())]
+        } else if m == 0 {
+            if self.f != self.g {
+                return Vec::new();
+            }
+            let mut points: Vec<ProjectivePoint<T>> = (0..T::cardinality())
+                .map(|i| ProjectivePoint::AtInfinity(T::element(i), T::identity()))
+                .collect();
+            points.push(ProjectivePoint::AtInfinity(T::identity(), T::zero()));
+            points.sort();
+            points
+        } else {
+            let mut v = vec![T::zero(); m + 1];
+            v[0] = T::zero() - self.g.leading_coefficient();
+            v[m] = self.f.leading_coefficient();
+            Polynomial::new(&v)
+                .distinct_roots()
+                .into_iter()
+                .map(|t| ProjectivePoint::AtInfinity(t, T::identity()))
+                .collect()
+        }
+    }
+}
+
+/// Z^d h(V/Z) に (V, Z) を代入した値
+fn evaluate_homogeneous<T>(h: &Polynomial<T>, v: T, z: T, d: usize) -> T
+where
+    T: Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+{
+    let mut ans = T::zero();
+    for (i, &c) in h.coefficients().iter().enumerate() {
+        let mut term = c;
+        for _ in 0..i {
+            term *= v;
+        }
+        for _ in i..d {
+            term *= z;
+        }
+        ans += term;
+    }
+    ans
+}
+
+/// Z^d h(V/Z) を変数名```variable```と Z で表示する
+fn print_homogeneous<T: fmt::Display + Zero + Identity + Eq>(
+    h: &Polynomial<T>,
+    variable: &str,
+    d: usize,
+) -> String {
+    let mut terms: Vec<String> = Vec::new();
+    for (i, c) in h.coefficients().iter().enumerate() {
+        if *c == T::zero() {
+            continue;
+        }
+        let mut s = String::new();
+        if *c != T::identity() || (i == 0 && d == 0) {
+            let c = c.to_string();
+            if c.contains(' ') {
+                s.push_str(&format!("({})", c));
+            } else {
+                s.push_str(&c);
+            }
+        }
+        for (name, e) in [(variable, i), ("Z", d - i)] {
+            if e >= 1 {
+                s.push_str(name);
+            }
+            if e > 1 {
+                s.push('^');
+                s.push_str(&e.to_string());
+            }
+        }
+        terms.push(s);
+    }
+    if terms.is_empty() {
+        "0".to_string()
+    } else {
+        terms.join(" + ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::random::Random;
+    use crate::testing::{polynomial, random_polynomial};
+
+    /// 無限遠直線上の点 (t : 1 : 0), (1 : 0 : 0) のうち曲線上にあるもの
+    fn points_at_infinity_by_search(
+        c: &ProjectiveClosure<DynModInt>,
+    ) -> Vec<ProjectivePoint<DynModInt>> {
+        let p = DynModInt::modulus();
+        let mut candidates: Vec<ProjectivePoint<DynModInt>> = (0..p)
+            .map(|t| ProjectivePoint::AtInfinity(DynModInt::new(t), DynModInt::identity()))
+            .collect();
+        candidates.push(ProjectivePoint::AtInfinity(
+            DynModInt::identity(),
+            DynModInt::zero(),
+        ));
+        candidates.sort();
+        candidates
+            .into_iter()
+            .filter(|point| c.contains(point))
+            .collect()
+    }
+
+    #[test]
+    fn elliptic_curve_closure() {
+        let _context = DynModContext::new(5);
+        let c = ProjectiveClosure::new(&polynomial(&[1, 1, 0, 1]), &polynomial(&[0, 0, 1]));
+        assert_eq!(c.degree(), 3);
+        assert_eq!(c.print(), "Z^3 + XZ^2 + X^3 = Y^2Z");
+        assert_eq!(
+            c.points_at_infinity(),
+            vec![ProjectivePoint::AtInfinity(
+                DynModInt::zero(),
+                DynModInt::identity()
+            )]
+        );
+        assert_eq!(c.points_at_infinity()[0].to_string(), "(0 : 1 : 0)");
+        assert!(c.contains(&ProjectivePoint::Affine(
+            DynModInt::new(0),
+            DynModInt::new(1)
+        )));
+        assert!(!c.contains(&ProjectivePoint::Affine(
+            DynModInt::new(1),
+            DynModInt::new(1)
+        )));
+    }
+
+    #[test]
+    fn equal_degrees_give_roots_at_infinity() {
+        // x^2 = y^2 + 1 の無限遠の点は t^2 = 1 の根 t = ±1 に対応する
+        let _context = DynModContext::new(5);
+        let c = ProjectiveClosure::new(&polynomial(&[0, 0, 1]), &polynomial(&[1, 0, 1]));
+        let one = DynModInt::identity();
+        assert_eq!(
+            c.points_at_infinity(),
+            vec![
+                ProjectivePoint::AtInfinity(one, one),
+                ProjectivePoint::AtInfinity(DynModInt::new(4), one)
+            ]
+        );
+        // x^2 = 2y^2 は t^2 = 2 に根が無いので無限遠の点を持たない
+        let c = ProjectiveClosure::new(
+            &polynomial::<DynModInt>(&[0, 0, 1]),
+            &polynomial(&[0, 0, 2]),
+        );
+        assert!(c.points_at_infinity().is_empty());
+    }
+
+    #[test]
+    fn constant_equation_contains_whole_line_at_infinity() {
+        // 1 = 1 は Z を含まないので、無限遠直線の p + 1 個の点がすべて曲線上にある
+        let _context = DynModContext::new(3);
+        let one = polynomial::<DynModInt>(&[1]);
+        let points = ProjectiveClosure::new(&one, &one).points_at_infinity();
+        let strings: Vec<String> = points.iter().map(|point| point.to_string()).collect();
+        assert_eq!(
+            strings,
+            vec!["(0 : 1 : 0)", "(1 : 0 : 0)", "(1 : 1 : 0)", "(2 : 1 : 0)"]
+        );
+        assert!(ProjectiveClosure::new(&one, &polynomial(&[2]))
+            .points_at_infinity()
+            .is_empty());
+        let zero = polynomial::<DynModInt>(&[0]);
+        assert_eq!(
+            ProjectiveClosure::new(&zero, &zero)
+                .points_at_infinity()
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn points_at_infinity_match_search() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7, 11] {
+            let _context = DynModContext::new(p);
+            for _ in 0..30 {
+                let f = random_polynomial::<DynModInt>(random.below(4) as usize, &mut random);
+                let g = random_polynomial::<DynModInt>(random.below(4) as usize, &mut random);
+                let c = ProjectiveClosure::new(&f, &g);
+                assert_eq!(
+                    c.points_at_infinity(),
+                    points_at_infinity_by_search(&c),
+                    "{} mod {}",
+                    c.print(),
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn affine_points_agree_with_equation() {
+        let _context = DynModContext::new(7);
+        let (f, g) = (polynomial(&[3, 0, 2, 1]), polynomial(&[1, 5, 0, 0, 1]));
+        let c = ProjectiveClosure::new(&f, &g);
+        for x in 0..7 {
+            for y in 0..7 {
+                let (x, y) = (DynModInt::new(x), DynModInt::new(y));
+                let on_curve = Polynomial::evaluate(&f, x) == Polynomial::evaluate(&g, y);
+                assert_eq!(c.contains(&ProjectivePoint::Affine(x, y)), on_curve);
+            }
+        }
+        // アフィンの点は無限遠の点より前に並ぶ
+        let one = DynModInt::identity();
+        assert!(
+            ProjectivePoint::Affine(DynModInt::new(6), DynModInt::new(6))
+                < ProjectivePoint::AtInfinity(DynModInt::zero(), one)
+        );
+        assert!(ProjectivePoint::AtInfinity(one, one).is_at_infinity());
+    }
+}
@@ -0,0 +1,78 @@
+/// 乱数生成器（xorshift64*）。暗号用ではなく、確率的アルゴリズムやテストで使うためのもの。
+///
+/// 同じシードからは同じ列が得られるので、結果は実行ごとに変わらない。
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// コンストラクタ。シードが```0```の場合は別の値に置き換える。
+    pub fn new(seed: u64) -> Self {
+        Random {
+            state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed },
+        }
+    }
+
+    /// 次の乱数
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// ```0```以上```n```未満の乱数。```n```は```1```以上とする。
+    pub fn below(&mut self, n: u64) -> u64 {
+        // 偏りをなくすため、n の倍数に収まらない値は捨てる
+        let limit = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < limit {
+                return x % n;
+            }
+        }
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Random::new(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+        // シード 0 は別の値に置き換えるので、0 だけの列にはならない
+        assert_ne!(Random::new(0).next_u64(), 0);
+    }
+
+    #[test]
+    fn below_is_in_range_and_roughly_uniform() {
+        let mut random = Random::default();
+        let n = 7;
+        let mut counts = [0; 7];
+        for _ in 0..70_000 {
+            let x = random.below(n);
+            assert!(x < n);
+            counts[x as usize] += 1;
+        }
+        assert!(
+            counts.iter().all(|&c| (9_000..11_000).contains(&c)),
+            "{:?}",
+            counts
+        );
+        assert_eq!(random.below(1), 0);
+        assert!(random.below(u64::MAX) < u64::MAX);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::convolution::Convolution;
+use crate::finite_ring::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
+use crate::polynomial::Polynomial;
+use std::ops::Mul;
+
+/// 係数環は体とする（割り算で最高次係数が可逆でないとパニックする）。
+impl<T: Convolution + Identity + Inverse + Eq> Polynomial<T> {
+    /// 終結式 Res(f, g)。f, g が共通根を持つ（次数1以上の共通因子を持つ）ときに限り```0```になる。
+    ///
+    /// m = deg f, n = deg g とし、g の根を β_j とすると Res(f, g) = lc(g)^m Π f(β_j)。
+    /// 定数 c と次数 n の多項式の終結式は c^n とし、零多項式は定数 0 とみなす
+    /// （```sylvester_matrix```の行列式と一致する）。
+    /// 互除法で Res(f, g) = (-1)^(mn) lc(g)^(m - k) Res(g, f mod g)（k = deg(f mod g)）を
+    /// 繰り返すので、計算量は O(mn)。
+    pub fn resultant(f: &Self, g: &Self) -> T {
+        let mut a = f.clone();
+        let mut b = g.clone();
+        let mut ans = T::identity();
+        loop {
+            let (m, n) = (a.deg(), b.deg());
+            if n == 0 {
+                return ans * power(b.leading_coefficient(), m);
+            }
+            let r = match a.div_rem(&b) {
+                Ok((_, r)) => r,
+                Err(e) => panic!("{}", e),
+            };
+            if r.is_zero() {
+                return T::zero();
+            }
+            if m % 2 == 1 && n % 2 == 1 {
+                ans = T::zero() - ans;
+            }
+            ans = ans * power(b.leading_coefficient(), m - r.deg());
+            a = b;
+            b = r;
+        }
+    }
+
+    /// 判別式 disc(f) = lc(f)^(2n - 2) Π_{i < j} (α_i - α_j)^2（n = deg f、α_i は f の根）。
+    /// f が重根を持つときに限り```0```になる。
+    ///
+    /// disc(f) = (-1)^(n(n - 1)/2) Res(f, f') / lc(f) で求める。ただし Res は f' を形式的に
+    /// n - 1 次とみたもので、標数 p で f' の次数が下がる場合も同じ式になるよう補正する。
+    /// 零多項式は```0```、零でない定数は```1```とする。
+    pub fn discriminant(f: &Self) -> T {
+        if f.is_zero() {
+            return T::zero();
+        }
+        let n = f.deg();
+        if n == 0 {
+            return T::identity();
+        }
+        let lc = f.leading_coefficient();
+        let d = Polynomial::derivative(f);
+        let mut r = Self::resultant(f, &d);
+        if !d.is_zero() {
+            // 形式的な次数 n - 1 の終結式は lc(f)^(n - 1 - k) Res(f, f')（k = deg f'）
+            r = r * power(lc, n - 1 - d.deg());
+        }
+        if (n * (n - 1) / 2) % 2 == 1 {
+            r = T::zero() - r;
+        }
+        r * lc.inverse().expect("最高次係数が可逆ではありません")
+    }
+}
+
+impl<T: Zero + Copy + Eq> Polynomial<T> {
+    /// Sylvester 行列。m = deg f, n = deg g として (m + n) 次の正方行列で、
+    /// 最初の n 行は f の係数を、残りの m 行は g の係数を、高次から順に1列ずつずらして並べる。
+    /// 行列式は```resultant(f, g)```に等しい。
+    pub fn sylvester_matrix(f: &Self, g: &Self) -> Vec<Vec<T>> {
+        let (m, n) = (f.deg(), g.deg());
+        let mut matrix = vec![vec![T::zero(); m + n]; m + n];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            let (h, shift) = if i < n { (f, i) } else { (g, i - n) };
+            for (j, &c) in h.coefficients().iter().rev().enumerate() {
+                row[shift + j] = c;
+            }
+        }
+        matrix
+    }
+}
+
+impl<T: FiniteField + Convolution + Identity + Eq + Ord> Polynomial<T> {
+    /// f(x) = c が重根を持つ、つまり disc(f - c) = 0 となる c を小さい順に並べて返す。
+    ///
+    /// このような c のファイバー f^(-1)(c) は、他の値より元が少なくなりうる（例外的なファイバー）。
+    /// disc(f - c) は c の高々 n - 1 次式（n = deg f）なので、n 個の c で求めて補間し、その根を求める。
+    /// 元の個数が n より少ない場合は、各 c で判別式を求める。f が定数なら空。
+    pub fn critical_values(f: &Self) -> Vec<T> {
+        let n = f.deg();
+        if n == 0 {
+            return Vec::new();
+        }
+        let disc = |c: T| Self::discriminant(&(f.clone() - Self::new_constant(c)));
+        if T::cardinality() < n as u64 {
+            return (0..T::cardinality())
+                .map(T::element)
+                .filter(|&c| disc(c) == T::zero())
+                .collect();
+        }
+        let cs: Vec<T> = (0..n as u64).map(T::element).collect();
+        let values: Vec<T> = cs.iter().map(|&c| disc(c)).collect();
+        let d = Self::interpolate(&cs, &values);
+        if d.is_zero() {
+            (0..T::cardinality()).map(T::element).collect()
+        } else {
+            d.distinct_roots()
+        }
+    }
+}
+
+/// ```t^e```（繰り返し二乗法）
+fn power<T: Identity + Mul<Output = T> + Copy>(t: T, e: usize) -> T {
+    let mut ans = T::identity();
+    let mut t = t;
+    let mut e = e;
+    while e > 0 {
+        if e % 2 == 1 {
+            ans = ans * t;
+        }
+        t = t * t;
+        e /= 2;
+    }
+    ans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::random::Random;
+    use crate::testing::{polynomial, random_polynomial};
+
+    /// 掃き出し法による行列式
+    fn determinant(mut a: Vec<Vec<DynModInt>>) -> DynModInt {
+        let n = a.len();
+        let mut det = DynModInt::identity();
+        for c in 0..n {
+            let r = match (c..n).find(|&r| a[r][c] != DynModInt::zero()) {
+                Some(r) => r,
+                None => return DynModInt::zero(),
+            };
+            if r != c {
+                a.swap(r, c);
+                det = DynModInt::zero() - det;
+            }
+            det *= a[c][c];
+            let inv = a[c][c].inverse().unwrap();
+            let pivot = a[c].clone();
+            for row in a.iter_mut().skip(c + 1) {
+                let k = row[c] * inv;
+                for (x, &t) in row.iter_mut().zip(&pivot).skip(c) {
+                    *x -= k * t;
+                }
+            }
+        }
+        det
+    }
+
+    /// 形式的に n - 1 次とみた f' との Sylvester 行列から判別式を求める
+    fn discriminant_by_sylvester(f: &Polynomial<DynModInt>) -> DynModInt {
+        let n = f.deg();
+        let d = Polynomial::derivative(f);
+        let mut rows = Polynomial::sylvester_matrix(f, &d);
+        // f' の行の前に、次数が下がった分の零を補って (2n - 1) 次にする
+        let size = 2 * n - 1;
+        let drop = n - 1 - d.deg();
+        let mut matrix = Vec::new();
+        for i in 0..(n - 1) {
+            let mut row = vec![DynModInt::zero(); size];
+            for (j, &c) in f.coefficients().iter().rev().enumerate() {
+                row[i + j] = c;
+            }
+            matrix.push(row);
+        }
+        for row in rows.drain(..).skip(d.deg()) {
+            let mut padded = vec![DynModInt::zero(); drop];
+            padded.extend(row);
+            matrix.push(padded);
+        }
+        let mut r = determinant(matrix);
+        if (n * (n - 1) / 2) % 2 == 1 {
+            r = DynModInt::zero() - r;
+        }
+        r * f.leading_coefficient().inverse().unwrap()
+    }
+
+    #[test]
+    fn resultant_equals_sylvester_determinant() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7, 101] {
+            let _context = DynModContext::new(p);
+            for _ in 0..50 {
+                let (m, n) = (random.below(6) as usize, random.below(6) as usize);
+                let f = random_polynomial(m, &mut random);
+                let g = random_polynomial(n, &mut random);
+                assert_eq!(
+                    determinant(Polynomial::sylvester_matrix(&f, &g)),
+                    Polynomial::resultant(&f, &g),
+                    "Res({}, {}) mod {}",
+                    f,
+                    g,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn discriminant_closed_forms() {
+        let mut random = Random::default();
+        for &p in &[3, 5, 7, 11, 101] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                let a = DynModInt::new(random.below(p));
+                let b = DynModInt::new(random.below(p));
+                let c = DynModInt::new(random.below(p));
+                let (four, twenty_seven) = (DynModInt::new(4), DynModInt::new(27));
+                // x^2 + bx + c
+                let quadratic = Polynomial::new(&[c, b, DynModInt::identity()]);
+                assert_eq!(Polynomial::discriminant(&quadratic), b * b - four * c);
+                // x^3 + ax + b
+                let cubic = Polynomial::new(&[b, a, DynModInt::zero(), DynModInt::identity()]);
+                assert_eq!(
+                    Polynomial::discriminant(&cubic),
+                    DynModInt::zero() - four * a * a * a - twenty_seven * b * b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn discriminant_when_derivative_degree_drops() {
+        // 標数 5 で x^5 + x^2 + 1 の微分は 2x なので、次数が 4 から 1 に下がる
+        let _context = DynModContext::new(5);
+        assert_eq!(
+            Polynomial::discriminant(&polynomial::<DynModInt>(&[1, 0, 1, 0, 0, 1])),
+            DynModInt::new(3)
+        );
+        assert_eq!(
+            Polynomial::discriminant(&polynomial::<DynModInt>(&[1, 0, 3, 0, 0, 2])),
+            DynModInt::new(1)
+        );
+    }
+
+    #[test]
+    fn discriminant_equals_sylvester_determinant() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7] {
+            let _context = DynModContext::new(p);
+            for _ in 0..100 {
+                let n = 1 + random.below(7) as usize;
+                let mut f = random_polynomial(n, &mut random);
+                if f.deg() == 0 {
+                    f += polynomial(&[0, 1]);
+                }
+                let d = Polynomial::derivative(&f);
+                if d.is_zero() {
+                    assert_eq!(Polynomial::discriminant(&f), DynModInt::zero());
+                    continue;
+                }
+                assert_eq!(
+                    Polynomial::discriminant(&f),
+                    discriminant_by_sylvester(&f),
+                    "disc({}) mod {}",
+                    f,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn critical_values_have_repeated_roots() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7, 13] {
+            let _context = DynModContext::new(p);
+            for _ in 0..30 {
+                let n = 1 + random.below(5) as usize;
+                let f = random_polynomial(n, &mut random);
+                if f.deg() == 0 {
+                    continue;
+                }
+                let d = Polynomial::derivative(&f);
+                let expected: Vec<DynModInt> = (0..p)
+                    .map(DynModInt::new)
+                    .filter(|&c| {
+                        let h = f.clone() - Polynomial::new_constant(c);
+                        d.is_zero() || Polynomial::gcd(&h, &d).deg() > 0
+                    })
+                    .collect();
+                assert_eq!(Polynomial::critical_values(&f), expected, "{} mod {}", f, p);
+            }
+        }
+    }
+}
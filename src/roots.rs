@@ -0,0 +1,211 @@
+use crate::convolution::Convolution;
+use crate::finite_ring::FiniteField;
+use crate::identities::Identity;
+use crate::polynomial::Polynomial;
+use crate::random::Random;
+
+/// 係数体は有限体 F_q とする。
+impl<T: FiniteField + Convolution + Identity + Eq + Ord> Polynomial<T> {
+    /// 変数 x そのものを表す1次式
+    pub fn x() -> Self {
+        Polynomial::new(&[T::zero(), T::identity()])
+    }
+
+    /// ```self^e mod modulus```を繰り返し二乗法で求める。```modulus```は零でないとする。
+    pub fn pow_mod(&self, e: u64, modulus: &Self) -> Self {
+        let mut res = Self::identity() % modulus.clone();
+        let mut a = self.clone() % modulus.clone();
+        let mut e = e;
+        while e > 0 {
+            if e % 2 == 1 {
+                res = (res * a.clone()) % modulus.clone();
+            }
+            a = (a.clone() * a) % modulus.clone();
+            e /= 2;
+        }
+        res
+    }
+
+    /// F_q に属する相異なる根を小さい順に並べて返す。零多項式の場合は空。
+    ///
+    /// gcd(f, x^q - x) で1次因子だけを取り出し、Cantor–Zassenhaus 法で分解する。
+    /// 体の元をすべて試すわけではないので、q が大きくても deg f と log q の多項式時間で済む。
+    pub fn distinct_roots(&self) -> Vec<T> {
+        if self.is_zero() || self.deg() == 0 {
+            return Vec::new();
+        }
+        let f = self.monic();
+        let x = Self::x();
+        let xq = x.pow_mod(T::cardinality(), &f);
+        let h = Self::gcd(&f, &(xq - x));
+        let mut roots = Vec::new();
+        let mut random = Random::default();
+        split_linear_factors(&h, &mut random, &mut roots);
+        roots.sort();
+        roots
+    }
+
+    /// F_q に属する根とその重複度の組を、根の小さい順に並べて返す。零多項式の場合は空。
+    pub fn roots(&self) -> Vec<(T, usize)> {
+        self.distinct_roots()
+            .into_iter()
+            .map(|r| {
+                let linear = Polynomial::new(&[T::zero() - r, T::identity()]);
+                let mut g = self.clone();
+                let mut multiplicity = 0;
+                while let Ok(q) = g.exact_div(&linear) {
+                    g = q;
+                    multiplicity += 1;
+                }
+                (r, multiplicity)
+            })
+            .collect()
+    }
+}
+
+/// 相異なる1次因子の積であるモニックな```h```を Cantor–Zassenhaus 法で分解し、根を```roots```に加える
+fn split_linear_factors<T>(h: &Polynomial<T>, random: &mut Random, roots: &mut Vec<T>)
+where
+    T: FiniteField + Convolution + Identity + Eq + Ord,
+{
+    if h.is_zero() || h.deg() == 0 {
+        return;
+    }
+    if h.deg() == 1 {
+        roots.push(T::zero() - h.coefficients()[0]);
+        return;
+    }
+    let d = loop {
+        let d = Polynomial::gcd(h, &splitting_polynomial(h, random));
+        if d.deg() > 0 && d.deg() < h.deg() {
+            break d;
+        }
+    };
+    let e = h.exact_div(&d).unwrap();
+    split_linear_factors(&d, random, roots);
+    split_linear_factors(&e, random, roots);
+}
+
+/// ```h```と gcd をとると確率 1/2 程度で```h```の因子が分かれる多項式を作る。
+///
+/// q が奇数なら (x + a)^((q - 1) / 2) - 1、q = 2^k なら a x のトレース
+/// Σ_{i < k} (a x)^(2^i) を```h```で割った余りとする（a はランダム）。
+fn splitting_polynomial<T>(h: &Polynomial<T>, random: &mut Random) -> Polynomial<T>
+where
+    T: FiniteField + Convolution + Identity + Eq + Ord,
+{
+    let q = T::cardinality();
+    let a = T::element(random.below(q));
+    if q % 2 == 1 {
+        let base = Polynomial::new(&[a, T::identity()]);
+        base.pow_mod((q - 1) / 2, h) - Polynomial::identity()
+    } else {
+        let mut term = Polynomial::new(&[T::zero(), a]) % h.clone();
+        let mut trace = term.clone();
+        for _ in 1..q.trailing_zeros() {
+            term = (term.clone() * term) % h.clone();
+            trace += term.clone();
+        }
+        trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::galois_field::{GaloisField, GfElem};
+    use crate::identities::Zero;
+    use crate::testing::random_polynomial;
+    use std::ops::{AddAssign, MulAssign};
+
+    /// すべての元を代入して根を求める
+    fn roots_by_evaluation<T>(f: &Polynomial<T>) -> Vec<T>
+    where
+        T: FiniteField + Convolution + Identity + MulAssign + AddAssign + Eq,
+    {
+        (0..T::cardinality())
+            .map(T::element)
+            .filter(|&t| Polynomial::evaluate(f, t) == T::zero())
+            .collect()
+    }
+
+    #[test]
+    fn distinct_roots_match_evaluation() {
+        let mut random = Random::new(1);
+        for &p in &[2, 3, 5, 7, 13, 101] {
+            let _context = DynModContext::new(p);
+            for _ in 0..50 {
+                let n = random.below(8) as usize;
+                let f = random_polynomial::<DynModInt>(n, &mut random);
+                if f.is_zero() {
+                    continue;
+                }
+                let mut expected = roots_by_evaluation(&f);
+                expected.sort();
+                assert_eq!(f.distinct_roots(), expected, "{} mod {}", f, p);
+            }
+        }
+    }
+
+    #[test]
+    fn distinct_roots_in_characteristic_two_extension() {
+        // q = 2^k では Cantor–Zassenhaus 法にトレースを使う
+        let mut random = Random::new(2);
+        let _field = GaloisField::with_degree(2, 4).unwrap();
+        for _ in 0..30 {
+            let n = random.below(8) as usize;
+            let f = random_polynomial::<GfElem>(n, &mut random);
+            if f.is_zero() {
+                continue;
+            }
+            let mut expected = roots_by_evaluation(&f);
+            expected.sort();
+            assert_eq!(f.distinct_roots(), expected, "{}", f);
+        }
+    }
+
+    #[test]
+    fn roots_with_multiplicity() {
+        let _context = DynModContext::new(7);
+        let linear = |r: u64| Polynomial::new(&[DynModInt::new(7 - r), DynModInt::identity()]);
+        // (x - 1)^3 (x - 4) (x^2 + 1)（x^2 + 1 は F_7 で既約）
+        let irreducible =
+            Polynomial::new(&[DynModInt::new(1), DynModInt::new(0), DynModInt::new(1)]);
+        let f = linear(1) * linear(1) * linear(1) * linear(4) * irreducible;
+        assert_eq!(
+            f.roots(),
+            vec![(DynModInt::new(1), 3), (DynModInt::new(4), 1)]
+        );
+        assert!(Polynomial::<DynModInt>::zero().roots().is_empty());
+        assert!(Polynomial::new_constant(DynModInt::new(3))
+            .roots()
+            .is_empty());
+    }
+
+    #[test]
+    fn distinct_roots_over_large_prime() {
+        let p = 1_000_000_000_039;
+        let _context = DynModContext::new(p);
+        let mut random = Random::new(3);
+        let mut expected: Vec<DynModInt> =
+            (0..5).map(|_| DynModInt::new(random.below(p))).collect();
+        let mut f = Polynomial::identity();
+        for &r in &expected {
+            f *= Polynomial::new(&[DynModInt::zero() - r, DynModInt::identity()]);
+        }
+        // 根を持たない2次式 x^2 - a（a は平方非剰余）を掛けても根は変わらない
+        let a = (2..)
+            .map(DynModInt::new)
+            .find(|a| a.modpow((p - 1) / 2) != DynModInt::identity())
+            .unwrap();
+        f *= Polynomial::new(&[
+            DynModInt::zero() - a,
+            DynModInt::zero(),
+            DynModInt::identity(),
+        ]);
+        expected.sort();
+        expected.dedup();
+        assert_eq!(f.distinct_roots(), expected);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::convolution::Convolution;
+use crate::finite_ring::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::polynomial::Polynomial;
+use crate::solver::solutions_by_roots;
+use std::fmt;
+use std::ops::MulAssign;
+
+/// 曲線 f(x) = g(y) の特異点
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SingularPoint<T> {
+    pub x: T,
+    pub y: T,
+    /// 重複度 min(r, s)。r, s は x, y がそれぞれ f(x) - f(x_0), g(y) - g(y_0) の何重根か。
+    pub multiplicity: usize,
+    /// 接錐
+    pub tangent_cone: TangentCone<T>,
+}
+
+/// 特異点 (x_0, y_0) での接錐 a u^m = b v^m（u = x - x_0, v = y - y_0, m は重複度）。
+///
+/// f(x_0 + u) - f(x_0) = Σ_{i >= r} a_i u^i、g(y_0 + v) - g(y_0) = Σ_{j >= s} b_j v^j と展開したときの
+/// 最低次の部分で、r > m なら a = 0、s > m なら b = 0 となる。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TangentCone<T> {
+    /// u^m の係数 a
+    pub u_coefficient: T,
+    /// v^m の係数 b
+    pub v_coefficient: T,
+    /// 次数 m
+    pub degree: usize,
+}
+
+impl<T> TangentCone<T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    /// F_q 上で定義される接線の方向 (du, dv) と重複度の組。
+    ///
+    /// b = 0 なら u = 0（方向 (0, 1)）、a = 0 なら v = 0（方向 (1, 0)）が m 重の接線。
+    /// そうでなければ a t^m = b の根 t ごとに u = t v（方向 (t, 1)）が接線になる。
+    pub fn tangent_lines(&self) -> Vec<(T, T, usize)> {
+        if self.v_coefficient == T::zero() {
+            vec![(T::zero(), T::identity(), self.degree)]
+        } else if self.u_coefficient == T::zero() {
+            vec![(T::identity(), T::zero(), self.degree)]
+        } else {
+            let mut v = vec![T::zero(); self.degree + 1];
+            v[0] = T::zero() - self.v_coefficient;
+            v[self.degree] = self.u_coefficient;
+            Polynomial::new(&v)
+                .roots()
+                .into_iter()
+                .map(|(t, k)| (t, T::identity(), k))
+                .collect()
+        }
+    }
+
+    /// 通常特異点（代数閉包上で相異なる m 本の接線をもつ）かどうか。
+    /// m = 2 の通常特異点は結節点で、そうでない m = 2 の特異点は尖点などになる。
+    pub fn is_ordinary(&self) -> bool {
+        self.u_coefficient != T::zero()
+            && self.v_coefficient != T::zero()
+            && !(self.degree as u64).is_multiple_of(T::characteristic())
+    }
+}
+
+/// ```a u^m = b v^m```の形で表示する
+impl<T: fmt::Display + Zero + Identity + Eq> fmt::Display for TangentCone<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = |c: &T, variable: &str| {
+            if *c == T::zero() {
+                return "0".to_string();
+            }
+            let mut s = String::new();
+            if *c != T::identity() {
+                s.push_str(&c.to_string());
+            }
+            s.push_str(variable);
+            if self.degree > 1 {
+                s.push('^');
+                s.push_str(&self.degree.to_string());
+            }
+            s
+        };
+        write!(
+            f,
+            "{} = {}",
+            side(&self.u_coefficient, "u"),
+            side(&self.v_coefficient, "v")
+        )
+    }
+}
+
+/// 曲線 f(x) = g(y) のアフィン特異点、すなわち f(x) = g(y), f'(x) = 0, g'(y) = 0 となる点を
+/// (x, y) の辞書式順に並べて返す。
+///
+/// f' の根を求め、各根 x について g(y) - f(x) の根のうち g'(y) = 0 となるものを選ぶ。
+/// f' = 0 の場合は x と y の役割を入れ替え、f' = g' = 0 の場合はすべての解が特異点になる。
+/// f, g がともに定数の場合は曲線を定めず重複度も決まらないので、空とする。
+pub fn singular_points<T>(f: &Polynomial<T>, g: &Polynomial<T>) -> Vec<SingularPoint<T>>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    if f.deg() == 0 && g.deg() == 0 {
+        return Vec::new();
+    }
+    let df = Polynomial::derivative(f);
+    let dg = Polynomial::derivative(g);
+    let mut points: Vec<(T, T)> = if !df.is_zero() {
+        df.distinct_roots()
+            .into_iter()
+            .flat_map(|x| {
+                let h = g.clone() - Polynomial::new_constant(Polynomial::evaluate(f, x));
+                fiber(&h)
+                    .into_iter()
+                    .filter(|&y| Polynomial::evaluate(&dg, y) == T::zero())
+                    .map(move |y| (x, y))
+            })
+            .collect()
+    } else if !dg.is_zero() {
+        dg.distinct_roots()
+            .into_iter()
+            .flat_map(|y| {
+                let h = f.clone() - Polynomial::new_constant(Polynomial::evaluate(g, y));
+                fiber(&h).into_iter().map(move |x| (x, y))
+            })
+            .collect()
+    } else {
+        solutions_by_roots(f, g).collect()
+    };
+    points.sort();
+    points
+        .into_iter()
+        .map(|(x, y)| {
+            let (r, a) = lowest_term(&taylor_shift(f, x));
+            let (s, b) = lowest_term(&taylor_shift(g, y));
+            // f, g の少なくとも一方は定数ではないので、重複度は有限
+            let multiplicity = r.min(s);
+            SingularPoint {
+                x,
+                y,
+                multiplicity,
+                tangent_cone: TangentCone {
+                    u_coefficient: if r == multiplicity { a } else { T::zero() },
+                    v_coefficient: if s == multiplicity { b } else { T::zero() },
+                    degree: multiplicity,
+                },
+            }
+        })
+        .collect()
+}
+
+/// 特異点が直線 x = x_0 や y = y_0 の全体、または f' = g' = 0 で曲線全体に広がり、
+/// ```singular_points```が体の元の個数に比例する個数の点を返しうる場合に```true```。
+pub fn has_singular_component<T>(f: &Polynomial<T>, g: &Polynomial<T>) -> bool
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    if f.deg() == 0 && g.deg() == 0 {
+        return false;
+    }
+    let df = Polynomial::derivative(f);
+    let dg = Polynomial::derivative(g);
+    // 片方が定数 c のとき、もう片方の臨界値が c なら直線全体が特異点になる
+    let line = |h: &Polynomial<T>, dh: &Polynomial<T>, c: &Polynomial<T>| {
+        c.deg() == 0
+            && dh
+                .distinct_roots()
+                .into_iter()
+                .any(|t| Polynomial::evaluate(h, t) == Polynomial::evaluate(c, T::zero()))
+    };
+    if !df.is_zero() {
+        line(f, &df, g)
+    } else if !dg.is_zero() {
+        line(g, &dg, f)
+    } else {
+        true
+    }
+}
+
+/// h(t) = 0 となる t。h が零多項式なら体のすべての元。
+fn fiber<T>(h: &Polynomial<T>) -> Vec<T>
+where
+    T: FiniteField + Convolution + Identity + Eq + Ord,
+{
+    if h.is_zero() {
+        (0..T::cardinality()).map(T::element).collect()
+    } else {
+        h.distinct_roots()
+    }
+}
+
+/// h(t + a) の係数。組立除法を繰り返すので標数によらず使える。計算量は O(deg^2)。
+pub fn taylor_shift<T>(h: &Polynomial<T>, a: T) -> Polynomial<T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    let mut c: Vec<T> = h.coefficients().to_vec();
+    let n = c.len();
+    for i in 0..n {
+        for j in (i..(n - 1)).rev() {
+            let t = c[j + 1] * a;
+            c[j] += t;
+        }
+    }
+    Polynomial::new(&c)
+}
+
+/// 定数項を除いた最低次の項の次数と係数。定数なら (```usize::MAX```, 0)。
+fn lowest_term<T>(h: &Polynomial<T>) -> (usize, T)
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    h.coefficients()
+        .iter()
+        .enumerate()
+        .skip(1)
+        .find(|(_, &c)| c != T::zero())
+        .map(|(i, &c)| (i, c))
+        .unwrap_or((usize::MAX, T::zero()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::random::Random;
+    use crate::testing::{polynomial, random_polynomial};
+
+    /// h(t) - h(t_0) が t - t_0 で何回割り切れるか。零多項式なら```usize::MAX```。
+    fn order_at(h: &Polynomial<DynModInt>, t0: DynModInt) -> usize {
+        let mut k = h.clone() - Polynomial::new_constant(Polynomial::evaluate(h, t0));
+        if k.is_zero() {
+            return usize::MAX;
+        }
+        let linear = Polynomial::new(&[DynModInt::zero() - t0, DynModInt::identity()]);
+        let mut order = 0;
+        while let Ok(q) = k.exact_div(&linear) {
+            k = q;
+            order += 1;
+        }
+        order
+    }
+
+    #[test]
+    fn node() {
+        // y^2 = x^2(x + 1) は原点に結節点をもち、接線は y = ±x
+        let _context = DynModContext::new(7);
+        let points = singular_points(&polynomial(&[0, 0, 1, 1]), &polynomial(&[0, 0, 1]));
+        assert_eq!(points.len(), 1);
+        let point = &points[0];
+        assert_eq!(
+            (point.x, point.y, point.multiplicity),
+            (DynModInt::zero(), DynModInt::zero(), 2)
+        );
+        assert_eq!(point.tangent_cone.to_string(), "u^2 = v^2");
+        assert!(point.tangent_cone.is_ordinary());
+        let one = DynModInt::identity();
+        assert_eq!(
+            point.tangent_cone.tangent_lines(),
+            vec![(one, one, 1), (DynModInt::new(6), one, 1)]
+        );
+    }
+
+    #[test]
+    fn cusp() {
+        // y^2 = x^3 は原点に尖点をもち、接錐は v^2 = 0（接線 v = 0 が2重）
+        let _context = DynModContext::new(7);
+        let points = singular_points(&polynomial(&[0, 0, 0, 1]), &polynomial(&[0, 0, 1]));
+        assert_eq!(points.len(), 1);
+        let point = &points[0];
+        assert_eq!(
+            (point.x, point.y, point.multiplicity),
+            (DynModInt::zero(), DynModInt::zero(), 2)
+        );
+        assert_eq!(
+            point.tangent_cone,
+            TangentCone {
+                u_coefficient: DynModInt::zero(),
+                v_coefficient: DynModInt::identity(),
+                degree: 2
+            }
+        );
+        assert_eq!(point.tangent_cone.to_string(), "0 = v^2");
+        assert!(!point.tangent_cone.is_ordinary());
+        assert_eq!(
+            point.tangent_cone.tangent_lines(),
+            vec![(DynModInt::identity(), DynModInt::zero(), 2)]
+        );
+    }
+
+    #[test]
+    fn smooth_curve_has_no_singular_points() {
+        let _context = DynModContext::new(5);
+        assert!(singular_points(
+            &polynomial::<DynModInt>(&[1, 1, 0, 1]),
+            &polynomial(&[0, 0, 1])
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn constant_equations_have_no_singular_points() {
+        // 1 = 1 は平面全体、1 = 0 は空集合で、どちらも曲線を定めない
+        let _context = DynModContext::new(2);
+        let (zero, one) = (polynomial::<DynModInt>(&[0]), polynomial(&[1]));
+        assert!(singular_points(&one, &one).is_empty());
+        assert!(singular_points(&one, &zero).is_empty());
+        assert!(!has_singular_component(&one, &one));
+    }
+
+    #[test]
+    fn singular_components() {
+        let _context = DynModContext::new(5);
+        // x^2 = 0 は直線 x = 0 全体が特異点
+        let (x2, zero) = (polynomial::<DynModInt>(&[0, 0, 1]), polynomial(&[0]));
+        assert!(has_singular_component(&x2, &zero));
+        assert!(has_singular_component(&zero, &x2));
+        assert_eq!(singular_points(&x2, &zero).len(), 5);
+        // x^2 = 1 は臨界値 0 が 1 と異なるので特異点を持たない
+        let one = polynomial(&[1]);
+        assert!(!has_singular_component(&x2, &one));
+        assert!(singular_points(&x2, &one).is_empty());
+        // 結節点だけを持つ曲線
+        assert!(!has_singular_component(&polynomial(&[0, 0, 1, 1]), &x2));
+        // x^5 = y^5 は f' = g' = 0 なので曲線全体が特異点
+        let x5 = polynomial::<DynModInt>(&[0, 0, 0, 0, 0, 1]);
+        assert!(has_singular_component(&x5, &x5));
+        assert_eq!(singular_points(&x5, &x5).len(), 5);
+    }
+
+    #[test]
+    fn singular_points_match_brute_force() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7] {
+            let _context = DynModContext::new(p);
+            for _ in 0..30 {
+                let f = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let g = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                if f.deg() == 0 && g.deg() == 0 {
+                    assert!(singular_points(&f, &g).is_empty());
+                    continue;
+                }
+                let (df, dg) = (Polynomial::derivative(&f), Polynomial::derivative(&g));
+                let mut expected = Vec::new();
+                for x in (0..p).map(DynModInt::new) {
+                    for y in (0..p).map(DynModInt::new) {
+                        if Polynomial::evaluate(&f, x) == Polynomial::evaluate(&g, y)
+                            && Polynomial::evaluate(&df, x) == DynModInt::zero()
+                            && Polynomial::evaluate(&dg, y) == DynModInt::zero()
+                        {
+                            expected.push((x, y, order_at(&f, x).min(order_at(&g, y))));
+                        }
+                    }
+                }
+                let actual: Vec<(DynModInt, DynModInt, usize)> = singular_points(&f, &g)
+                    .iter()
+                    .map(|point| (point.x, point.y, point.multiplicity))
+                    .collect();
+                assert_eq!(actual, expected, "{} = {} mod {}", f, g.print_f_of_y(), p);
+            }
+        }
+    }
+
+    #[test]
+    fn taylor_shift_evaluates_at_shifted_point() {
+        let mut random = Random::default();
+        let _context = DynModContext::new(11);
+        for _ in 0..20 {
+            let h = random_polynomial::<DynModInt>(random.below(6) as usize, &mut random);
+            let a = DynModInt::new(random.below(11));
+            let shifted = taylor_shift(&h, a);
+            for t in (0..11).map(DynModInt::new) {
+                assert_eq!(
+                    Polynomial::evaluate(&shifted, t),
+                    Polynomial::evaluate(&h, t + a)
+                );
+            }
+        }
+    }
+}
@@ -1,54 +1,169 @@
-use std::cmp::Eq;
+use std::collections::BTreeSet;
 use std::fmt;
-use std::hash::Hash;
+use std::iter::FromIterator;
 
 /// 解集合
-#[derive(Clone, Debug)]
-pub struct SolutionSet<T: Hash> {
-    solutions: std::collections::HashSet<T>,
+///
+/// 元は順序付きで持つので、繰り返しや表示は常に小さい順になる。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolutionSet<T: Ord> {
+    solutions: BTreeSet<T>,
 }
 
-impl<T: Hash> SolutionSet<T> {
+impl<T: Ord> SolutionSet<T> {
     /// コンストラクタ。面倒なので所有権が移ることにしてしまった。
-    pub fn new(s: std::collections::HashSet<T>) -> Self {
+    pub fn new(s: BTreeSet<T>) -> Self {
         Self { solutions: s }
     }
 
     pub fn size(&self) -> usize {
         self.solutions.len()
     }
+
+    /// 元を加える。
+    pub fn insert(&mut self, t: T) {
+        self.solutions.insert(t);
+    }
+
+    /// 元を含むかどうか
+    pub fn contains(&self, t: &T) -> bool {
+        self.solutions.contains(t)
+    }
+
+    /// 元を小さい順に返すイテレータ
+    pub fn iter(&self) -> std::collections::btree_set::Iter<'_, T> {
+        self.solutions.iter()
+    }
+
+    /// ```other```の部分集合かどうか
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.solutions.is_subset(&other.solutions)
+    }
+
+    /// ```other```を部分集合として含むかどうか
+    pub fn is_superset(&self, other: &Self) -> bool {
+        self.solutions.is_superset(&other.solutions)
+    }
+
+    /// ```other```と共通の元を持たないかどうか
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.solutions.is_disjoint(&other.solutions)
+    }
 }
 
-impl<T: Hash + Clone> SolutionSet<T> {
-    pub fn unwrap(&self) -> std::collections::HashSet<T> {
+impl<T: Ord + Clone> SolutionSet<T> {
+    pub fn unwrap(&self) -> BTreeSet<T> {
         self.solutions.clone()
     }
+
+    /// 和集合
+    pub fn union(&self, other: &Self) -> Self {
+        self.solutions.union(&other.solutions).cloned().collect()
+    }
+
+    /// 共通部分
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.solutions
+            .intersection(&other.solutions)
+            .cloned()
+            .collect()
+    }
+
+    /// 差集合（```self```にあって```other```にないもの）
+    pub fn difference(&self, other: &Self) -> Self {
+        self.solutions
+            .difference(&other.solutions)
+            .cloned()
+            .collect()
+    }
+
+    /// 対称差
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.solutions
+            .symmetric_difference(&other.solutions)
+            .cloned()
+            .collect()
+    }
 }
 
-impl<T: Hash + Eq> SolutionSet<T> {
-    /// 元を加える。
-    pub fn insert(&mut self, t: T) {
-        self.solutions.insert(t);
+/// イテレータから解集合を作れるようにする。
+impl<T: Ord> FromIterator<T> for SolutionSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            solutions: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a SolutionSet<T> {
+    type Item = &'a T;
+    type IntoIter = std::collections::btree_set::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.solutions.iter()
     }
 }
 
 /// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
 /// 型```T```がそもそも```Display```トレイトを実装していることを要求。
-impl<T: fmt::Display + Hash> fmt::Display for SolutionSet<T> {
+/// 元は小さい順に並べるので、同じ集合は常に同じ文字列になる。
+impl<T: fmt::Display + Ord> fmt::Display for SolutionSet<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut s: String = String::new();
-        if self.solutions.len() == 0 {
-            s.push_str(&"{ }");
+        if self.solutions.is_empty() {
+            s.push_str("{ }");
         } else {
-            s.push_str(&"{");
+            s.push('{');
             for solution in &self.solutions {
                 s.push_str(&solution.to_string());
-                s.push_str(&", ");
+                s.push_str(", ");
             }
             s.pop();
             s.pop();
-            s.push_str(&"}");
+            s.push('}');
         }
         write!(f, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(v: &[(u64, u64)]) -> SolutionSet<(u64, u64)> {
+        v.iter().copied().collect()
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let b = set(&[(1, 2), (3, 4), (5, 6)]);
+        assert_eq!(a.union(&b), set(&[(0, 1), (1, 2), (2, 3), (3, 4), (5, 6)]));
+        assert_eq!(a.intersection(&b), set(&[(1, 2), (3, 4)]));
+        assert_eq!(a.difference(&b), set(&[(0, 1), (2, 3)]));
+        assert_eq!(b.difference(&a), set(&[(5, 6)]));
+        assert_eq!(a.symmetric_difference(&b), set(&[(0, 1), (2, 3), (5, 6)]));
+        assert!(a.intersection(&b).is_subset(&a));
+        assert!(a.union(&b).is_superset(&b));
+        assert!(a.difference(&b).is_disjoint(&b));
+        assert!(!a.is_disjoint(&b));
+        let empty = set(&[]);
+        assert_eq!(a.union(&empty), a);
+        assert_eq!(a.intersection(&empty), empty);
+        assert!(empty.is_subset(&a));
+    }
+
+    #[test]
+    fn ordered_iteration_and_display() {
+        // 加えた順によらず小さい順に並ぶ
+        let mut s = set(&[(2, 0), (0, 5)]);
+        s.insert((1, 1));
+        s.insert((0, 5));
+        assert_eq!(s.size(), 3);
+        assert!(s.contains(&(1, 1)));
+        let v: Vec<&(u64, u64)> = s.iter().collect();
+        assert_eq!(v, vec![&(0, 5), &(1, 1), &(2, 0)]);
+        let numbers: SolutionSet<u64> = vec![3, 1, 2].into_iter().collect();
+        assert_eq!(numbers.to_string(), "{1, 2, 3}");
+        assert_eq!(SolutionSet::<u64>::new(BTreeSet::new()).to_string(), "{ }");
+    }
+}
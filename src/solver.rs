@@ -0,0 +1,484 @@
+use crate::bivariate::BivariatePolynomial;
+use crate::convolution::Convolution;
+use crate::finite_ring::{FiniteField, FiniteRing};
+use crate::identities::{Identity, Zero};
+use crate::polynomial::Polynomial;
+use crate::solution_set::SolutionSet;
+use std::collections::BTreeSet;
+use std::ops::{AddAssign, Mul, MulAssign};
+
+/// 多項式の値ごとに元を分類したもの（各値のファイバー）。
+///
+/// 元や値は```FiniteRing```の番号で持つ。各ファイバーの中では番号の小さい順に並ぶ。
+#[derive(Clone, Debug)]
+pub struct Fibers {
+    /// 値```c```のファイバーは```members[offsets[c]..offsets[c + 1]]```
+    offsets: Vec<usize>,
+    members: Vec<u64>,
+}
+
+impl Fibers {
+    /// 各元で```f```を1回ずつ評価して、値ごとに元を分類する。
+    pub fn new<T>(f: &Polynomial<T>) -> Self
+    where
+        T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+    {
+        let q = T::cardinality() as usize;
+        let values: Vec<u64> = (0..T::cardinality())
+            .map(|i| Polynomial::evaluate(f, T::element(i)).index())
+            .collect();
+        // 数え上げソートで値ごとにまとめる
+        let mut offsets = vec![0; q + 1];
+        for &c in &values {
+            offsets[c as usize + 1] += 1;
+        }
+        for c in 0..q {
+            offsets[c + 1] += offsets[c];
+        }
+        let mut next = offsets.clone();
+        let mut members = vec![0; q];
+        for (i, &c) in values.iter().enumerate() {
+            members[next[c as usize]] = i as u64;
+            next[c as usize] += 1;
+        }
+        Fibers { offsets, members }
+    }
+
+    /// 値（の番号）が```c```である元の番号の列
+    pub fn fiber(&self, c: u64) -> &[u64] {
+        &self.members[self.offsets[c as usize]..self.offsets[c as usize + 1]]
+    }
+}
+
+/// 方程式 f(x) = g(y) の解を1つずつ返すイテレータ。```solutions```で作る。
+///
+/// 解は (x, y) の番号の辞書式順に返す。
+/// 前もって持つのは g のファイバーだけなので、解の個数によらずメモリは O(p)。
+#[derive(Clone, Debug)]
+pub struct Solutions<'a, T> {
+    f: &'a Polynomial<T>,
+    fibers: Fibers,
+    /// 次に調べる x の番号
+    next_x: u64,
+    /// 今調べている x とその f(x) の番号
+    current: Option<(T, u64)>,
+    /// 今調べている x のファイバーの中で次に返す位置
+    position: usize,
+}
+
+impl<'a, T> Iterator for Solutions<'a, T>
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+{
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<(T, T)> {
+        loop {
+            if let Some((x, c)) = self.current {
+                if let Some(&j) = self.fibers.fiber(c).get(self.position) {
+                    self.position += 1;
+                    return Some((x, T::element(j)));
+                }
+            }
+            if self.next_x >= T::cardinality() {
+                return None;
+            }
+            let x = T::element(self.next_x);
+            self.current = Some((x, Polynomial::evaluate(self.f, x).index()));
+            self.position = 0;
+            self.next_x += 1;
+        }
+    }
+}
+
+/// 方程式 f(x) = g(y) の解を順に返すイテレータを作る。
+///
+/// g を各元で1回ずつ評価して値ごとのファイバーを作り、
+/// 各 x について f(x) のファイバーの元を y として並べる。
+/// 途中で止めれば残りの解は計算しない。
+pub fn solutions<'a, T>(f: &'a Polynomial<T>, g: &Polynomial<T>) -> Solutions<'a, T>
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+{
+    Solutions {
+        f,
+        fibers: Fibers::new(g),
+        next_x: 0,
+        current: None,
+        position: 0,
+    }
+}
+
+/// 方程式 f(x) = g(y) の解を求める。
+///
+/// ```solutions```で得られる解をすべて集める。
+/// 計算量は O(p・deg + 解の個数)。
+pub fn solve_equation<T>(f: &Polynomial<T>, g: &Polynomial<T>) -> SolutionSet<(T, T)>
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Ord,
+{
+    solutions(f, g).collect()
+}
+
+/// ```RootSolutions```などで、今の x に対する y の候補
+#[derive(Clone, Debug)]
+enum Candidates<T> {
+    /// g(y) - f(x) の根（小さい順）と次に返す位置
+    Roots(Vec<T>, usize),
+    /// g(y) - f(x) が零多項式なので、すべての y が解になる。次に返す y の番号。
+    All(u64),
+}
+
+impl<T> Candidates<T>
+where
+    T: FiniteField + Convolution + Identity + Eq + Ord,
+{
+    /// y の多項式 h の根を候補とする
+    fn new(h: &Polynomial<T>) -> Self {
+        if h.is_zero() {
+            Candidates::All(0)
+        } else {
+            Candidates::Roots(h.distinct_roots(), 0)
+        }
+    }
+
+    /// 次の候補
+    fn next(&mut self) -> Option<T> {
+        match self {
+            Candidates::Roots(ys, position) => {
+                let y = ys.get(*position).copied();
+                if y.is_some() {
+                    *position += 1;
+                }
+                y
+            }
+            Candidates::All(j) => {
+                if *j < T::cardinality() {
+                    *j += 1;
+                    Some(T::element(*j - 1))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// 方程式 f(x) = g(y) の解を、各 x について g(y) - f(x) の根を求めることで
+/// 1つずつ返すイテレータ。```solutions_by_roots```で作る。
+///
+/// 解は (x, y) の番号の辞書式順に返す。値の表を作らないので、
+/// p が非常に大きく```solutions```が使えない場合でも、最初のいくつかの解を求められる。
+#[derive(Clone, Debug)]
+pub struct RootSolutions<'a, T> {
+    f: &'a Polynomial<T>,
+    g: &'a Polynomial<T>,
+    /// 次に調べる x の番号
+    next_x: u64,
+    /// 今調べている x と、それに対する y の候補
+    current: Option<(T, Candidates<T>)>,
+}
+
+impl<'a, T> Iterator for RootSolutions<'a, T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<(T, T)> {
+        loop {
+            if let Some((x, candidates)) = &mut self.current {
+                if let Some(y) = candidates.next() {
+                    return Some((*x, y));
+                }
+            }
+            if self.next_x >= T::cardinality() {
+                return None;
+            }
+            let x = T::element(self.next_x);
+            let h = self.g.clone() - Polynomial::new_constant(Polynomial::evaluate(self.f, x));
+            self.current = Some((x, Candidates::new(&h)));
+            self.next_x += 1;
+        }
+    }
+}
+
+/// 方程式 f(x) = g(y) の解を、各 x について g(y) - f(x) の根を求めて順に返すイテレータを作る。
+///
+/// 係数環は有限体とする。x 1つあたりの計算量は deg g と log p の多項式で、
+/// 値の表を持たないので p が 10^12 程度でも最初の解からすぐに出力できる。
+pub fn solutions_by_roots<'a, T>(f: &'a Polynomial<T>, g: &'a Polynomial<T>) -> RootSolutions<'a, T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    RootSolutions {
+        f,
+        g,
+        next_x: 0,
+        current: None,
+    }
+}
+
+/// 方程式 F(x, y) = 0 の解を、各 x について F(x, y) の y の多項式としての根を求めて
+/// 1つずつ返すイテレータ。```bivariate_solutions```で作る。
+///
+/// ```RootSolutions```と同じく、解は (x, y) の番号の辞書式順に返す。
+#[derive(Clone, Debug)]
+pub struct BivariateSolutions<'a, T> {
+    f: &'a BivariatePolynomial<T>,
+    /// 次に調べる x の番号
+    next_x: u64,
+    /// 今調べている x と、それに対する y の候補
+    current: Option<(T, Candidates<T>)>,
+}
+
+impl<'a, T> Iterator for BivariateSolutions<'a, T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<(T, T)> {
+        loop {
+            if let Some((x, candidates)) = &mut self.current {
+                if let Some(y) = candidates.next() {
+                    return Some((*x, y));
+                }
+            }
+            if self.next_x >= T::cardinality() {
+                return None;
+            }
+            let x = T::element(self.next_x);
+            self.current = Some((x, Candidates::new(&self.f.substitute_x(x))));
+            self.next_x += 1;
+        }
+    }
+}
+
+/// 方程式 F(x, y) = 0 の解を順に返すイテレータを作る。係数環は有限体とする。
+///
+/// x と y の積の項を含む一般の2変数多項式に使える。x 1つあたりの計算量は
+/// F の項の個数と、y についての次数および log q の多項式。
+pub fn bivariate_solutions<T>(f: &BivariatePolynomial<T>) -> BivariateSolutions<'_, T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    BivariateSolutions {
+        f,
+        next_x: 0,
+        current: None,
+    }
+}
+
+/// 方程式 F(x, y) = 0 の解集合
+pub fn solve_bivariate<T>(f: &BivariatePolynomial<T>) -> SolutionSet<(T, T)>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    bivariate_solutions(f).collect()
+}
+
+/// 方程式 F(x, y) = 0 の解の個数。各 x について根の個数だけを数える。
+pub fn count_bivariate_solutions<T>(f: &BivariatePolynomial<T>) -> u64
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    (0..T::cardinality())
+        .map(|i| {
+            let h = f.substitute_x(T::element(i));
+            if h.is_zero() {
+                T::cardinality()
+            } else {
+                h.distinct_roots().len() as u64
+            }
+        })
+        .sum()
+}
+
+/// 方程式 F(x, y) = 0 の解を全探索で求める。計算量は O(q^2・(項の個数))。
+/// 係数環が体でなくても使える。
+pub fn solve_bivariate_brute_force<T>(f: &BivariatePolynomial<T>) -> SolutionSet<(T, T)>
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Ord,
+{
+    let mut s: BTreeSet<(T, T)> = BTreeSet::new();
+    for i in 0..T::cardinality() {
+        for j in 0..T::cardinality() {
+            let (x, y) = (T::element(i), T::element(j));
+            if f.evaluate(x, y) == T::zero() {
+                s.insert((x, y));
+            }
+        }
+    }
+    SolutionSet::new(s)
+}
+
+/// 各値（の番号）```c```について、f(x) = c となる x の個数を並べたもの。
+pub fn value_counts<T>(f: &Polynomial<T>) -> Vec<u64>
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+{
+    let mut counts = vec![0; T::cardinality() as usize];
+    for i in 0..T::cardinality() {
+        counts[Polynomial::evaluate(f, T::element(i)).index() as usize] += 1;
+    }
+    counts
+}
+
+/// 方程式 f(x) = g(y) の解の個数を、解を列挙せずに求める。
+///
+/// 解の個数は Σ_c |f^{-1}(c)|・|g^{-1}(c)| なので、値ごとの個数だけを数える。
+/// 使うメモリは O(p)。
+pub fn count_solutions<T>(f: &Polynomial<T>, g: &Polynomial<T>) -> u64
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq,
+{
+    let cf = value_counts(f);
+    let cg = value_counts(g);
+    cf.iter().zip(cg.iter()).map(|(a, b)| a * b).sum()
+}
+
+/// 方程式 f(x) = g(y) の解を全探索で求める。計算量は O(p^2・deg)。
+pub fn solve_equation_brute_force<T>(
+    f: &Polynomial<T>,
+    g: &Polynomial<T>,
+) -> SolutionSet<(T, T)>
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Ord,
+{
+    let mut s: BTreeSet<(T, T)> = BTreeSet::new();
+    for i in 0..T::cardinality() {
+        for j in 0..T::cardinality() {
+            if Polynomial::evaluate(f, T::element(i)) == Polynomial::evaluate(g, T::element(j)) {
+                s.insert((T::element(i), T::element(j)));
+            }
+        }
+    }
+    SolutionSet::new(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::modint::ModInt;
+    use crate::random::Random;
+    use crate::testing::random_polynomial;
+
+    #[test]
+    fn solve_equation_matches_brute_force() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7, 11, 31] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                let f = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let g = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                assert_eq!(
+                    solve_equation(&f, &g),
+                    solve_equation_brute_force(&f, &g),
+                    "{} = {} mod {}",
+                    f,
+                    g.print_f_of_y(),
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn solve_equation_over_composite_modulus() {
+        // 体でなくても、値の表で解ける
+        let mut random = Random::default();
+        for _ in 0..20 {
+            let f = random_polynomial::<ModInt<12>>(random.below(4) as usize, &mut random);
+            let g = random_polynomial::<ModInt<12>>(random.below(4) as usize, &mut random);
+            assert_eq!(solve_equation(&f, &g), solve_equation_brute_force(&f, &g));
+        }
+    }
+
+    #[test]
+    fn known_solutions() {
+        // x^2 = y^2 mod 5 の解は y = ±x の 9 個
+        let _context = DynModContext::new(5);
+        let square = Polynomial::new(&[DynModInt::new(0), DynModInt::new(0), DynModInt::new(1)]);
+        let s = solve_equation(&square, &square);
+        assert_eq!(s.size(), 9);
+        for x in 0..5 {
+            assert!(s.contains(&(DynModInt::new(x), DynModInt::new(x))));
+            assert!(s.contains(&(DynModInt::new(x), DynModInt::new(5 - x))));
+        }
+    }
+
+    #[test]
+    fn count_solutions_matches_brute_force() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7, 11, 31] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                let f = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let g = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let expected = solve_equation_brute_force(&f, &g).size() as u64;
+                assert_eq!(
+                    count_solutions(&f, &g),
+                    expected,
+                    "{} = {} mod {}",
+                    f,
+                    g.print_f_of_y(),
+                    p
+                );
+                assert_eq!(solutions(&f, &g).count() as u64, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn value_counts_sum_to_cardinality() {
+        let mut random = Random::default();
+        let _context = DynModContext::new(13);
+        for _ in 0..20 {
+            let f = random_polynomial::<DynModInt>(random.below(6) as usize, &mut random);
+            let counts = value_counts(&f);
+            assert_eq!(counts.iter().sum::<u64>(), 13);
+            for (c, &n) in counts.iter().enumerate() {
+                assert_eq!(Fibers::new(&f).fiber(c as u64).len() as u64, n);
+            }
+        }
+    }
+
+    #[test]
+    fn solutions_are_in_lexicographic_order() {
+        let mut random = Random::default();
+        for &p in &[2, 5, 17] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                let f = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let g = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let expected: Vec<(DynModInt, DynModInt)> =
+                    solve_equation_brute_force(&f, &g).iter().copied().collect();
+                let streamed: Vec<(DynModInt, DynModInt)> = solutions(&f, &g).collect();
+                assert_eq!(streamed, expected, "{} = {} mod {}", f, g.print_f_of_y(), p);
+                // 途中で止めると最初の解だけが得られる
+                let limit = random.below(expected.len() as u64 + 2) as usize;
+                let first: Vec<(DynModInt, DynModInt)> = solutions(&f, &g).take(limit).collect();
+                assert_eq!(first[..], expected[..limit.min(expected.len())]);
+            }
+        }
+    }
+
+    #[test]
+    fn solutions_by_roots_match_brute_force() {
+        // g が定数で g(y) - f(x) が零多項式になる x では、すべての y が解になる
+        let mut random = Random::default();
+        for &p in &[2, 3, 7, 13] {
+            let _context = DynModContext::new(p);
+            for _ in 0..20 {
+                let f = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let g = random_polynomial::<DynModInt>(random.below(5) as usize, &mut random);
+                let expected: Vec<(DynModInt, DynModInt)> =
+                    solve_equation_brute_force(&f, &g).iter().copied().collect();
+                let by_roots: Vec<(DynModInt, DynModInt)> = solutions_by_roots(&f, &g).collect();
+                assert_eq!(by_roots, expected, "{} = {} mod {}", f, g.print_f_of_y(), p);
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use crate::bivariate::BivariatePolynomial;
+use crate::convolution::Convolution;
+use crate::finite_ring::{FiniteField, FiniteRing};
+use crate::identities::{Identity, Zero};
+use crate::polynomial::Polynomial;
+use crate::solution_set::SolutionSet;
+use std::collections::BTreeSet;
+use std::ops::{AddAssign, Mul, MulAssign};
+
+/// 連立方程式 F_1(x, y) = 0, ..., F_k(x, y) = 0 の共通解の集合。係数環は有限体とする。
+///
+/// y についての次数の低い（安い）方程式から順に並べ、最初の2つから終結式で y を消去して
+/// x の候補を絞る。各 x では F_i(x, y) の y の多項式としての最大公約式を安い順に取り、
+/// その根を y とする。最大公約式が定数になった時点で、その x には解がないとして打ち切る。
+/// f(x) = g(y) の形の方程式は```BivariatePolynomial::from_x(f) - BivariatePolynomial::from_y(g)```
+/// として与える。方程式がすべて零多項式の場合（空の場合も含む）は、すべての (x, y) が解になる。
+pub fn solve_system<T>(equations: &[BivariatePolynomial<T>]) -> SolutionSet<(T, T)>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    let mut equations: Vec<&BivariatePolynomial<T>> =
+        equations.iter().filter(|h| !h.is_zero()).collect();
+    equations.sort_by_key(|h| (h.deg_y(), h.deg_x()));
+
+    let xs: Vec<T> = match equations.get(..2).and_then(|e| eliminate_y(e[0], e[1])) {
+        Some(r) => r.distinct_roots(),
+        None => (0..T::cardinality()).map(T::element).collect(),
+    };
+    let mut s: BTreeSet<(T, T)> = BTreeSet::new();
+    for x in xs {
+        for y in common_roots(&equations, x) {
+            s.insert((x, y));
+        }
+    }
+    SolutionSet::new(s)
+}
+
+/// 連立方程式の共通解を全探索で求める。計算量は O(q^2・(項の個数の和))。
+/// 係数環が体でなくても使える。
+pub fn solve_system_brute_force<T>(equations: &[BivariatePolynomial<T>]) -> SolutionSet<(T, T)>
+where
+    T: FiniteRing + Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Ord,
+{
+    let mut s: BTreeSet<(T, T)> = BTreeSet::new();
+    for i in 0..T::cardinality() {
+        for j in 0..T::cardinality() {
+            let (x, y) = (T::element(i), T::element(j));
+            if equations.iter().all(|h| h.evaluate(x, y) == T::zero()) {
+                s.insert((x, y));
+            }
+        }
+    }
+    SolutionSet::new(s)
+}
+
+/// y の多項式 F_i(x, y)（x は固定）の共通根。すべて零多項式ならすべての元。
+fn common_roots<T>(equations: &[&BivariatePolynomial<T>], x: T) -> Vec<T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    let mut h: Polynomial<T> = Polynomial::zero();
+    for e in equations {
+        h = Polynomial::gcd(&h, &e.substitute_x(x));
+        if h.deg() == 0 && !h.is_zero() {
+            return Vec::new();
+        }
+    }
+    if h.is_zero() {
+        (0..T::cardinality()).map(T::element).collect()
+    } else {
+        h.distinct_roots()
+    }
+}
+
+/// y についての終結式 R(x) = Res_y(F_1, F_2)。共通解 (x, y) の x は R の根になる。
+///
+/// n_i = deg_y F_i、m_i = deg_x F_i とすると deg R ≤ m_1 n_2 + m_2 n_1 なので、
+/// y の最高次係数が消えない x でその個数より1つ多く1変数の終結式を計算し、補間する。
+/// 補間に使う点が足りない場合、点の個数が多く全ての x を調べた方が安い場合、
+/// R が零多項式になる（F_1, F_2 が共通因子を持つ）場合、両方とも y を含まない場合は```None```。
+fn eliminate_y<T>(f1: &BivariatePolynomial<T>, f2: &BivariatePolynomial<T>) -> Option<Polynomial<T>>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    let (n1, n2) = (f1.deg_y(), f2.deg_y());
+    if n1 == 0 && n2 == 0 {
+        return None;
+    }
+    let bound = f1.deg_x() * n2 + f2.deg_x() * n1;
+    if (bound as u64 + 1).saturating_mul(bound as u64 + 1) > T::cardinality() {
+        return None;
+    }
+    let (a1, a2) = (leading_coefficient_in_y(f1), leading_coefficient_in_y(f2));
+    let mut xs: Vec<T> = Vec::new();
+    let mut values: Vec<T> = Vec::new();
+    for i in 0..T::cardinality() {
+        if xs.len() > bound {
+            break;
+        }
+        let x = T::element(i);
+        if Polynomial::evaluate(&a1, x) == T::zero() || Polynomial::evaluate(&a2, x) == T::zero() {
+            continue;
+        }
+        xs.push(x);
+        values.push(Polynomial::resultant(&f1.substitute_x(x), &f2.substitute_x(x)));
+    }
+    if xs.len() <= bound {
+        return None;
+    }
+    let r = Polynomial::interpolate(&xs, &values);
+    if r.is_zero() {
+        None
+    } else {
+        Some(r)
+    }
+}
+
+/// y の最高次の項の係数を x の多項式として返す
+fn leading_coefficient_in_y<T>(h: &BivariatePolynomial<T>) -> Polynomial<T>
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    let n = h.deg_y();
+    let mut v = vec![T::zero(); h.deg_x() + 1];
+    for ((i, j), c) in h.terms() {
+        if j == n {
+            v[i] += c;
+        }
+    }
+    Polynomial::new(&v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dyn_modint::{DynModContext, DynModInt};
+    use crate::galois_field::{GaloisField, GfElem};
+    use crate::random::Random;
+    use crate::testing::random_bivariate;
+
+    fn check_system(equations: &[BivariatePolynomial<DynModInt>], p: u64) {
+        let names: Vec<String> = equations.iter().map(|h| h.to_string()).collect();
+        assert_eq!(
+            solve_system(equations),
+            solve_system_brute_force(equations),
+            "{} mod {}",
+            names.join(", "),
+            p
+        );
+    }
+
+    #[test]
+    fn solver_matches_brute_force() {
+        let mut random = Random::default();
+        for &p in &[2, 3, 5, 7, 11] {
+            let _context = DynModContext::new(p);
+            for _ in 0..40 {
+                let k = random.below(4) as usize;
+                let equations: Vec<BivariatePolynomial<DynModInt>> =
+                    (0..k).map(|_| random_bivariate(3, &mut random)).collect();
+                check_system(&equations, p);
+            }
+        }
+        let _field = GaloisField::with_degree(3, 2).unwrap();
+        for _ in 0..20 {
+            let equations: Vec<BivariatePolynomial<GfElem>> =
+                (0..2).map(|_| random_bivariate(2, &mut random)).collect();
+            assert_eq!(
+                solve_system(&equations),
+                solve_system_brute_force(&equations)
+            );
+        }
+    }
+
+    #[test]
+    fn solver_uses_resultant_for_large_fields() {
+        // (次数の上限 + 1)^2 ≤ p なので終結式による消去が使われる
+        let mut random = Random::default();
+        for &(p, trials) in &[(101, 30), (1009, 2)] {
+            let _context = DynModContext::new(p);
+            for _ in 0..trials {
+                let f1 = random_bivariate(2, &mut random);
+                let f2 = random_bivariate(2, &mut random);
+                check_system(&[f1.clone(), f2.clone()], p);
+                // 共通因子を持つと終結式は零多項式になり、すべての x を調べる
+                let g = random_bivariate(1, &mut random);
+                check_system(&[f1 * g.clone(), f2 * g], p);
+            }
+        }
+    }
+
+    #[test]
+    fn eliminated_polynomial_is_resultant() {
+        let mut random = Random::default();
+        let _context = DynModContext::new(101);
+        let mut checked = 0;
+        while checked < 20 {
+            let f1 = random_bivariate::<DynModInt>(2, &mut random);
+            let f2 = random_bivariate::<DynModInt>(2, &mut random);
+            let r = match eliminate_y(&f1, &f2) {
+                Some(r) => r,
+                None => continue,
+            };
+            checked += 1;
+            let (a1, a2) = (leading_coefficient_in_y(&f1), leading_coefficient_in_y(&f2));
+            for i in 0..101 {
+                let x = DynModInt::new(i);
+                if Polynomial::evaluate(&a1, x) == DynModInt::zero()
+                    || Polynomial::evaluate(&a2, x) == DynModInt::zero()
+                {
+                    continue;
+                }
+                let expected = Polynomial::resultant(&f1.substitute_x(x), &f2.substitute_x(x));
+                assert_eq!(
+                    Polynomial::evaluate(&r, x),
+                    expected,
+                    "Res({}, {}) at {}",
+                    f1,
+                    f2,
+                    x
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn interpolate_recovers_polynomial() {
+        let mut random = Random::default();
+        let _context = DynModContext::new(101);
+        for n in 0..20 {
+            let v: Vec<DynModInt> = (0..n).map(|_| DynModInt::new(random.below(101))).collect();
+            let f = Polynomial::new(&v);
+            let xs: Vec<DynModInt> = (0..n as u64 + 1)
+                .map(|i| DynModInt::new(3 * i + 1))
+                .collect();
+            let values: Vec<DynModInt> = xs.iter().map(|&x| Polynomial::evaluate(&f, x)).collect();
+            assert_eq!(Polynomial::interpolate(&xs, &values), f);
+        }
+    }
+
+    #[test]
+    fn degenerate_systems() {
+        let _context = DynModContext::new(5);
+        assert_eq!(solve_system::<DynModInt>(&[]).size(), 25);
+        assert_eq!(
+            solve_system(&[BivariatePolynomial::<DynModInt>::zero()]).size(),
+            25
+        );
+        // y を含まない2式: x = 1 と x^2 = 1
+        let one = DynModInt::identity();
+        let f1 = BivariatePolynomial::new(&[((1, 0), one), ((0, 0), DynModInt::new(4))]);
+        let f2 = BivariatePolynomial::new(&[((2, 0), one), ((0, 0), DynModInt::new(4))]);
+        assert_eq!(eliminate_y(&f1, &f2), None);
+        check_system(&[f1, f2], 5);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::bivariate::BivariatePolynomial;
+use crate::finite_ring::FiniteRing;
+use crate::identities::Zero;
+use crate::polynomial::Polynomial;
+use crate::random::Random;
+use std::ops::Add;
+
+/// 係数を0次から順に元の番号で与えた多項式
+pub fn polynomial<T: FiniteRing + Zero + Copy + Eq>(v: &[u64]) -> Polynomial<T> {
+    let w: Vec<T> = v.iter().map(|&c| T::element(c)).collect();
+    Polynomial::new(&w)
+}
+
+/// 次数が```n```以下のランダムな多項式（n + 1 個の係数を一様に選ぶ）
+pub fn random_polynomial<T: FiniteRing + Zero + Copy + Eq>(
+    n: usize,
+    random: &mut Random,
+) -> Polynomial<T> {
+    let v: Vec<T> = (0..=n)
+        .map(|_| T::element(random.below(T::cardinality())))
+        .collect();
+    Polynomial::new(&v)
+}
+
+/// x, y の次数がそれぞれ```n```以下で、項が5個以下のランダムな2変数多項式
+pub fn random_bivariate<T: FiniteRing + Zero + Add<Output = T> + Copy + Eq>(
+    n: usize,
+    random: &mut Random,
+) -> BivariatePolynomial<T> {
+    let n = n as u64;
+    let terms: Vec<((usize, usize), T)> = (0..random.below(6))
+        .map(|_| {
+            let e = (random.below(n + 1) as usize, random.below(n + 1) as usize);
+            (e, T::element(random.below(T::cardinality())))
+        })
+        .collect();
+    BivariatePolynomial::new(&terms)
+}
@@ -0,0 +1,251 @@
+use crate::convolution::Convolution;
+use crate::finite_ring::{FiniteField, FiniteRing};
+use crate::galois_field::{GaloisField, GfElem};
+use crate::identities::Identity;
+use crate::polynomial::Polynomial;
+use crate::solver::count_solutions;
+use std::ops::MulAssign;
+
+// 曲線 C: f(x) = g(y)（m = deg f, n = deg g, d = gcd(m, n)）の射影非特異モデルについて計算する。
+//
+// 以下では、アフィン部分が非特異であり、p が d を割り切らないことを仮定する。
+// このとき無限遠の点は a w^d = b（a, b は f, g の最高次係数、w = x^(m/d) / y^(n/d)）の根と
+// 1対1に対応し、種数は Newton 多角形の内部の格子点の個数 ((m-1)(n-1) + 1 - d) / 2 になる。
+
+/// 曲線 f(x) = g(y) の種数。```m```, ```n```は f, g の次数、```p```は標数。
+///
+/// 次数が```0```の場合や、p が gcd(m, n) を割り切る場合は、この方法では求められないので```None```。
+pub fn genus(m: usize, n: usize, p: u64) -> Option<usize> {
+    if m == 0 || n == 0 {
+        return None;
+    }
+    let d = num::Integer::gcd(&m, &n);
+    if (d as u64).is_multiple_of(p) {
+        return None;
+    }
+    Some(((m - 1) * (n - 1) + 1 - d) / 2)
+}
+
+/// 射影非特異モデルの無限遠の点のうち、現在の体 F_q に属するものの個数。
+/// 射影閉包（特異点を解消しないもの）の無限遠の点は```ProjectiveClosure::points_at_infinity```で求める。
+pub fn points_at_infinity_smooth<T>(f: &Polynomial<T>, g: &Polynomial<T>) -> u64
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    if f.deg() == 0 || g.deg() == 0 {
+        return 0;
+    }
+    let d = num::Integer::gcd(&f.deg(), &g.deg());
+    let mut v = vec![T::zero(); d + 1];
+    v[0] = T::zero() - g.leading_coefficient();
+    v[d] = f.leading_coefficient();
+    Polynomial::new(&v).distinct_roots().len() as u64
+}
+
+/// 射影非特異モデルの F_q 有理点の個数（アフィンの解の個数と無限遠の点の個数の和）
+pub fn point_count<T>(f: &Polynomial<T>, g: &Polynomial<T>) -> u64
+where
+    T: FiniteField + Convolution + Identity + MulAssign + Eq + Ord,
+{
+    count_solutions(f, g) + points_at_infinity_smooth(f, g)
+}
+
+/// F_p 上の係数（0次から順）で与えた曲線 f(x) = g(y) について、k = 1, ..., ```k_max```の
+/// F_{p^k} 有理点の個数 N_k を並べて返す。
+///
+/// 各 k について```GaloisField::with_degree```で F_{p^k} を作り、値の表で数えるので、
+/// p^k の大きさのメモリを使う。
+pub fn point_counts(p: u64, f: &[u64], g: &[u64], k_max: usize) -> Vec<u64> {
+    (1..=k_max)
+        .map(|k| {
+            let _field = GaloisField::with_degree(p, k).unwrap();
+            let f: Vec<GfElem> = f.iter().map(|&c| GfElem::element(c)).collect();
+            let g: Vec<GfElem> = g.iter().map(|&c| GfElem::element(c)).collect();
+            point_count(&Polynomial::new(&f), &Polynomial::new(&g))
+        })
+        .collect()
+}
+
+/// 点の個数 N_1, ..., N_g から、ゼータ関数 Z(T) = L(T) / ((1 - T)(1 - qT)) の分子
+/// L(T) の係数（定数項から順に 2g + 1 個）を求める。```counts```が g 個に満たなければ```None```。
+///
+/// L(T) = Π (1 - α_i T) とすると N_k = q^k + 1 - Σ α_i^k なので、Newton の恒等式で
+/// 前半の係数を求め、後半は関数等式 c_{2g-j} = q^(g-j) c_j から求める。
+pub fn l_polynomial(q: u64, genus: usize, counts: &[u64]) -> Option<Vec<i128>> {
+    if counts.len() < genus {
+        return None;
+    }
+    let q = q as i128;
+    // s[k] = Σ α_i^k
+    let mut s = vec![0; genus + 1];
+    let mut power = 1;
+    for k in 1..=genus {
+        power *= q;
+        s[k] = power + 1 - counts[k - 1] as i128;
+    }
+    let mut c = vec![0; 2 * genus + 1];
+    c[0] = 1;
+    for j in 1..=genus {
+        let sum: i128 = (1..=j).map(|i| s[i] * c[j - i]).sum();
+        c[j] = -sum / j as i128;
+    }
+    for j in (genus + 1)..=(2 * genus) {
+        c[j] = q.pow((j - genus) as u32) * c[2 * genus - j];
+    }
+    Some(c)
+}
+
+/// L(T) から点の個数 N_1, ..., N_{k_max} を求める。```l_polynomial```の逆で、
+/// 数えた N_k と比べれば仮定が成り立っているかを確かめられる。
+pub fn counts_from_l_polynomial(q: u64, l: &[i128], k_max: usize) -> Vec<i128> {
+    let q = q as i128;
+    let coefficient = |j: usize| l.get(j).copied().unwrap_or(0);
+    let mut s: Vec<i128> = vec![0; k_max + 1];
+    let mut counts = Vec::new();
+    let mut power = 1;
+    for k in 1..=k_max {
+        // k c_k + Σ_{i=1}^{k} s_i c_{k-i} = 0
+        let sum: i128 = (1..k).map(|i| s[i] * coefficient(k - i)).sum();
+        s[k] = -(k as i128) * coefficient(k) - sum;
+        power *= q;
+        counts.push(power + 1 - s[k]);
+    }
+    counts
+}
+
+/// 整数係数の多項式を T の多項式として表示する（例: 1 - 2T + 5T^2）
+pub fn print_l_polynomial(l: &[i128]) -> String {
+    let mut s = String::new();
+    for (j, &c) in l.iter().enumerate() {
+        if c == 0 {
+            continue;
+        }
+        if s.is_empty() {
+            if c < 0 {
+                s.push('-');
+            }
+        } else if c < 0 {
+            s.push_str(" - ");
+        } else {
+            s.push_str(" + ");
+        }
+        if j == 0 || c.abs() != 1 {
+            s.push_str(&c.abs().to_string());
+        }
+        if j >= 1 {
+            s.push('T');
+        }
+        if j > 1 {
+            s.push('^');
+            s.push_str(&j.to_string());
+        }
+    }
+    if s.is_empty() {
+        s.push('0');
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve_equation_brute_force;
+    use crate::testing::polynomial;
+
+    #[test]
+    fn genus_of_separated_curves() {
+        // y^2 = x^3 + ... は楕円曲線、y^2 = x^5 + ... は種数 2
+        assert_eq!(genus(3, 2, 5), Some(1));
+        assert_eq!(genus(4, 2, 5), Some(1));
+        assert_eq!(genus(5, 2, 3), Some(2));
+        // Fermat 曲線 x^4 = -y^4 + 1 は種数 3
+        assert_eq!(genus(4, 4, 3), Some(3));
+        assert_eq!(genus(1, 5, 7), Some(0));
+        assert_eq!(genus(2, 2, 2), None);
+        assert_eq!(genus(0, 3, 5), None);
+    }
+
+    #[test]
+    fn elliptic_curve_over_f5() {
+        // y^2 = x^3 + x + 1 は F_5 上に 8 個のアフィンの点と 1 個の無限遠の点をもつ
+        let (f, g) = (vec![1, 1, 0, 1], vec![0, 0, 1]);
+        let counts = point_counts(5, &f, &g, 3);
+        assert_eq!(counts, vec![9, 27, 108]);
+        let l = l_polynomial(5, 1, &counts).unwrap();
+        assert_eq!(l, vec![1, 3, 5]);
+        assert_eq!(print_l_polynomial(&l), "1 + 3T + 5T^2");
+        assert_eq!(counts_from_l_polynomial(5, &l, 3), vec![9, 27, 108]);
+    }
+
+    #[test]
+    fn point_count_matches_brute_force() {
+        // F_25 上で数えても、アフィンの点は全探索と一致する
+        let _field = GaloisField::with_degree(5, 2).unwrap();
+        let f = polynomial::<GfElem>(&[1, 1, 0, 1]);
+        let g = polynomial(&[0, 0, 1]);
+        let affine = solve_equation_brute_force(&f, &g).size() as u64;
+        assert_eq!(point_count(&f, &g), affine + 1);
+        assert_eq!(point_count(&f, &g), 27);
+    }
+
+    #[test]
+    fn points_at_infinity_of_smooth_model() {
+        {
+            let _field = GaloisField::with_degree(5, 1).unwrap();
+            // y^2 = x^4 + 1: w^2 = 1 の根 w = ±1 に対応する 2 点
+            assert_eq!(
+                points_at_infinity_smooth(
+                    &polynomial::<GfElem>(&[1, 0, 0, 0, 1]),
+                    &polynomial(&[0, 0, 1])
+                ),
+                2
+            );
+            // y^2 = 2x^4 + 1: w^2 = 3 は F_5 に根を持たない
+            assert_eq!(
+                points_at_infinity_smooth(
+                    &polynomial::<GfElem>(&[1, 0, 0, 0, 2]),
+                    &polynomial(&[0, 0, 1])
+                ),
+                0
+            );
+            // 次数が互いに素なら 1 点
+            assert_eq!(
+                points_at_infinity_smooth(
+                    &polynomial::<GfElem>(&[1, 1, 0, 1]),
+                    &polynomial(&[0, 0, 1])
+                ),
+                1
+            );
+            assert_eq!(
+                points_at_infinity_smooth(&polynomial::<GfElem>(&[1]), &polynomial(&[0, 0, 1])),
+                0
+            );
+        }
+        // F_25 では 3 が平方になる
+        let _field = GaloisField::with_degree(5, 2).unwrap();
+        let f = polynomial::<GfElem>(&[1, 0, 0, 0, 2]);
+        let g = polynomial(&[0, 0, 1]);
+        assert_eq!(points_at_infinity_smooth(&f, &g), 2);
+    }
+
+    #[test]
+    fn l_polynomial_of_genus_two_curve() {
+        // y^2 = x^5 + 1 over F_3（種数 2）。N_1, N_2 から求めた L(T) で N_3, N_4 も説明できる
+        let counts = point_counts(3, &[1, 0, 0, 0, 0, 1], &[0, 0, 1], 4);
+        let l = l_polynomial(3, 2, &counts).unwrap();
+        assert_eq!(l.len(), 5);
+        assert_eq!((l[0], l[4]), (1, 9));
+        let expected: Vec<i128> = counts.iter().map(|&n| n as i128).collect();
+        assert_eq!(counts_from_l_polynomial(3, &l, 4), expected);
+        assert_eq!(l_polynomial(3, 2, &counts[..1]), None);
+    }
+
+    #[test]
+    fn print_integer_polynomials() {
+        assert_eq!(print_l_polynomial(&[1, -2, 5]), "1 - 2T + 5T^2");
+        assert_eq!(print_l_polynomial(&[-1, 0, -1]), "-1 - T^2");
+        assert_eq!(print_l_polynomial(&[0, 1]), "T");
+        assert_eq!(print_l_polynomial(&[0]), "0");
+        assert_eq!(print_l_polynomial(&[]), "0");
+    }
+}